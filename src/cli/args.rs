@@ -0,0 +1,286 @@
+use std::{fs, slice};
+
+use crate::types::{
+    app::{BodyMode, RawType, method_from_label},
+    request::{RequestSpec, has_header, parse_key_value_lines, validate_json},
+};
+
+/// Help text for the flags shared by the TUI, `parsel send` and `parsel run`.
+pub const REQUEST_FLAGS: &str = "\
+Request:
+  -X, --method <METHOD>    HTTP method (default: GET, or POST with a body)
+  -H, --header <H: V>      add a header (repeatable)
+  -q, --query <K=V>        add a query parameter (repeatable)
+  -j, --json <K=V>         add a JSON body field; dotted keys nest (repeatable)
+  -f, --form <K=V>         add a urlencoded form field (repeatable)
+  -F, --multipart <K=V>    add a multipart field, `K=@path` uploads a file (repeatable)
+  -d, --data <BODY>        use a raw body, `@path` reads it from a file
+      --body-file <PATH>   use the contents of a file as a raw body
+  -o, --option <K=V>       set a request option such as timeout=5s (repeatable)
+      --env <NAME>         resolve {{variables}} from this environment";
+
+/// Walks the command line, splitting `--flag=value` into flag and value.
+pub struct ArgCursor<'a> {
+    args: slice::Iter<'a, String>,
+    inline: Option<&'a str>,
+}
+
+impl<'a> ArgCursor<'a> {
+    pub fn new(args: &'a [String]) -> Self {
+        Self {
+            args: args.iter(),
+            inline: None,
+        }
+    }
+
+    /// The next flag or positional argument.
+    pub fn next_arg(&mut self) -> Option<&'a str> {
+        let arg = self.args.next()?;
+        match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                self.inline = Some(value);
+                Some(flag)
+            }
+            _ => {
+                self.inline = None;
+                Some(arg)
+            }
+        }
+    }
+
+    /// The value of `flag`, given inline or as the next argument.
+    pub fn value(&mut self, flag: &str) -> Result<String, String> {
+        match self.inline.take() {
+            Some(value) => Ok(value.to_string()),
+            None => self
+                .args
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {flag}")),
+        }
+    }
+}
+
+/// Request flags layered over a [`RequestSpec`]. `target` is the positional
+/// argument: a URL, or a saved request id for `parsel run`.
+#[derive(Debug, Default)]
+pub struct RequestArgs {
+    pub target: Option<String>,
+    pub env: Option<String>,
+    method: Option<String>,
+    headers: Vec<String>,
+    query: Vec<(String, String)>,
+    body_mode: Option<BodyMode>,
+    fields: Vec<(String, String)>,
+    data: Option<String>,
+    options: Vec<(String, String)>,
+}
+
+impl RequestArgs {
+    /// Consumes `flag` if it is a request flag; returns `false` otherwise.
+    pub fn parse_flag(&mut self, flag: &str, args: &mut ArgCursor) -> Result<bool, String> {
+        match flag {
+            "-X" | "--method" => {
+                let method = args.value(flag)?;
+                if method_from_label(&method).is_none() {
+                    return Err(format!("Unknown HTTP method `{method}`"));
+                }
+                self.method = Some(method.to_ascii_uppercase());
+            }
+            "-H" | "--header" => {
+                let header = args.value(flag)?;
+                if !header.contains(':') {
+                    return Err(format!("Expected `Name: value` for {flag}, got `{header}`"));
+                }
+                self.headers.push(header);
+            }
+            "-q" | "--query" => self.query.push(key_value(flag, &args.value(flag)?)?),
+            "-j" | "--json" => self.add_field(BodyMode::Json, flag, &args.value(flag)?)?,
+            "-f" | "--form" => self.add_field(BodyMode::Form, flag, &args.value(flag)?)?,
+            "-F" | "--multipart" => {
+                self.add_field(BodyMode::Multipart, flag, &args.value(flag)?)?
+            }
+            "-d" | "--data" => {
+                let data = args.value(flag)?;
+                match data.strip_prefix('@') {
+                    Some(path) => self.set_data(read_body(path)?)?,
+                    None => self.set_data(data)?,
+                }
+            }
+            "--body-file" => self.set_data(read_body(&args.value(flag)?)?)?,
+            "-o" | "--option" => self.options.push(key_value(flag, &args.value(flag)?)?),
+            "--env" => self.env = Some(args.value(flag)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Takes an argument no flag consumed as the target.
+    pub fn positional(&mut self, arg: &str) -> Result<(), String> {
+        if arg.starts_with('-') && arg.len() > 1 {
+            return Err(format!("Unknown option {arg}"));
+        }
+        if self.target.is_some() {
+            return Err(format!("Unexpected argument `{arg}`"));
+        }
+        self.target = Some(arg.to_string());
+        Ok(())
+    }
+
+    /// Whether any flag edits the request itself.
+    pub fn edits_request(&self) -> bool {
+        self.method.is_some()
+            || !self.headers.is_empty()
+            || !self.query.is_empty()
+            || self.body_mode.is_some()
+            || !self.options.is_empty()
+    }
+
+    fn set_body_mode(&mut self, mode: BodyMode) -> Result<(), String> {
+        match self.body_mode {
+            Some(current) if current != mode => Err(
+                "Only one of --json, --form, --multipart and --data/--body-file can be used"
+                    .to_string(),
+            ),
+            Some(_) if mode == BodyMode::Raw => {
+                Err("A raw body can only be given once".to_string())
+            }
+            _ => {
+                self.body_mode = Some(mode);
+                Ok(())
+            }
+        }
+    }
+
+    fn add_field(&mut self, mode: BodyMode, flag: &str, value: &str) -> Result<(), String> {
+        self.set_body_mode(mode)?;
+        self.fields.push(key_value(flag, value)?);
+        Ok(())
+    }
+
+    fn set_data(&mut self, data: String) -> Result<(), String> {
+        self.set_body_mode(BodyMode::Raw)?;
+        self.data = Some(data);
+        Ok(())
+    }
+
+    /// Layers the flags over `spec`: headers, query parameters and options
+    /// are added, a body replaces the existing one.
+    pub fn apply(&self, spec: &mut RequestSpec) {
+        if let Some(method) = &self.method {
+            spec.method = method.clone();
+        } else if spec.method.is_empty() {
+            spec.method = if self.body_mode.is_some() {
+                "POST"
+            } else {
+                "GET"
+            }
+            .to_string();
+        }
+        append_lines(&mut spec.headers, self.headers.iter().cloned());
+        append_lines(&mut spec.query, pair_lines(&self.query));
+        append_lines(&mut spec.options, pair_lines(&self.options));
+        let Some(mode) = self.body_mode else {
+            return;
+        };
+        spec.body_mode = mode;
+        match &self.data {
+            Some(data) => {
+                let headers = parse_key_value_lines(&spec.headers);
+                spec.raw_type = if has_header(&headers, "content-type") {
+                    RawType::Custom
+                } else if validate_json(data).is_ok() {
+                    RawType::Json
+                } else {
+                    RawType::Text
+                };
+                spec.body = data.clone();
+            }
+            None => spec.body = pair_lines(&self.fields).collect::<Vec<_>>().join("\n"),
+        }
+    }
+}
+
+fn read_body(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))
+}
+
+fn key_value(flag: &str, value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("Expected `key=value` for {flag}, got `{value}`"))
+}
+
+fn pair_lines(pairs: &[(String, String)]) -> impl Iterator<Item = String> + '_ {
+    pairs.iter().map(|(key, value)| format!("{key}: {value}"))
+}
+
+fn append_lines(target: &mut String, lines: impl Iterator<Item = String>) {
+    for line in lines {
+        if !target.trim_end().is_empty() {
+            target.truncate(target.trim_end().len());
+            target.push('\n');
+        }
+        target.push_str(&line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArgCursor, RequestArgs};
+    use crate::types::{
+        app::{BodyMode, RawType},
+        request::RequestSpec,
+    };
+
+    fn parse(line: &str) -> Result<RequestArgs, String> {
+        let args = line
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let mut cursor = ArgCursor::new(&args);
+        let mut request = RequestArgs::default();
+        while let Some(arg) = cursor.next_arg() {
+            if !request.parse_flag(arg, &mut cursor)? {
+                request.positional(arg)?;
+            }
+        }
+        Ok(request)
+    }
+
+    #[test]
+    fn request_flags_build_a_spec() {
+        let args = parse(
+            "https://x.io/users -H Accept:text/plain --query=page=2 -j user.name=Ada -j age=3 -o timeout=5s",
+        )
+        .unwrap();
+        let mut spec = RequestSpec::default();
+        args.apply(&mut spec);
+        assert_eq!(args.target.as_deref(), Some("https://x.io/users"));
+        assert_eq!(spec.method, "POST");
+        assert_eq!(spec.headers, "Accept:text/plain");
+        assert_eq!(spec.query, "page: 2");
+        assert_eq!(spec.body_mode, BodyMode::Json);
+        assert_eq!(spec.body, "user.name: Ada\nage: 3");
+        assert_eq!(spec.options, "timeout: 5s");
+
+        let args = parse("-X put u -d [1,2]").unwrap();
+        let mut spec = RequestSpec {
+            headers: "X-Saved: 1\n".to_string(),
+            ..Default::default()
+        };
+        args.apply(&mut spec);
+        assert_eq!(spec.method, "PUT");
+        assert_eq!(
+            (spec.body_mode, spec.raw_type),
+            (BodyMode::Raw, RawType::Json)
+        );
+
+        assert!(parse("u -j a=1 -f b=2").is_err());
+        assert!(parse("u -q novalue").is_err());
+        assert!(parse("u --nope").is_err());
+        assert!(!parse("u --env dev").unwrap().edits_request());
+    }
+}
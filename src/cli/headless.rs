@@ -0,0 +1,293 @@
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+    sync::atomic::AtomicBool,
+    time::Instant,
+};
+
+use curl_rest::Response;
+
+use crate::{
+    cli::{
+        EXIT_STATUS, EXIT_TRANSPORT, EXIT_USAGE,
+        args::{ArgCursor, REQUEST_FLAGS, RequestArgs},
+    },
+    types::{
+        binary::{format_size, is_binary},
+        charset::decode_body,
+        collection::{CollectionItem, Collections},
+        cookies::CookieJar,
+        environment::Environments,
+        history::unix_timestamp,
+        request::{RequestSpec, SendError},
+    },
+    ui::sections::response_body::format_for_display,
+};
+
+const OUTPUT_FLAGS: &str = "\
+Output:
+      --raw                print only the response body, as received
+      --fail               exit with 1 on a 4xx or 5xx status
+      --expect <CODES>     exit with 1 unless the status matches, e.g. 200,201 or 2xx
+  -h, --help               show this help
+
+Exit codes: 0 success, 1 unexpected status, 2 invalid arguments or request,
+3 connection failure or timeout.";
+
+fn send_usage() -> String {
+    format!(
+        "Usage: parsel send [OPTIONS] <URL>\n\nSends one request and prints the response.\n\n{REQUEST_FLAGS}\n\n{OUTPUT_FLAGS}"
+    )
+}
+
+fn run_usage() -> String {
+    format!(
+        "Usage: parsel run [OPTIONS] <folder/name>\n\n\
+         Sends a request saved in .parsel/collections and prints the response. Request\n\
+         flags are applied on top of the saved request: headers, query parameters and\n\
+         options are added, a body replaces the saved one.\n\n{REQUEST_FLAGS}\n\n{OUTPUT_FLAGS}"
+    )
+}
+
+/// Accepted status codes: an exact code or a whole class such as `2xx`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StatusPattern {
+    Exact(u16),
+    Class(u16),
+}
+
+impl StatusPattern {
+    fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some(class) = value.strip_suffix("xx")
+            && let Ok(class @ 1..=5) = class.parse::<u16>()
+        {
+            return Ok(Self::Class(class));
+        }
+        match value.parse::<u16>() {
+            Ok(code @ 100..=599) => Ok(Self::Exact(code)),
+            _ => Err(format!(
+                "Invalid status `{value}`, expected a code or a class like 2xx"
+            )),
+        }
+    }
+
+    fn matches(self, code: u16) -> bool {
+        match self {
+            Self::Exact(expected) => code == expected,
+            Self::Class(class) => code / 100 == class,
+        }
+    }
+}
+
+/// Parsed `send`/`run` arguments.
+#[derive(Debug, Default)]
+struct Invocation {
+    request: RequestArgs,
+    raw: bool,
+    fail: bool,
+    expect: Vec<StatusPattern>,
+    help: bool,
+}
+
+impl Invocation {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut invocation = Self::default();
+        let mut args = ArgCursor::new(args);
+        while let Some(arg) = args.next_arg() {
+            if invocation.request.parse_flag(arg, &mut args)? {
+                continue;
+            }
+            match arg {
+                "--raw" => invocation.raw = true,
+                "--fail" => invocation.fail = true,
+                "--expect" => {
+                    for code in args.value(arg)?.split(',') {
+                        if !code.trim().is_empty() {
+                            invocation.expect.push(StatusPattern::parse(code)?);
+                        }
+                    }
+                }
+                "-h" | "--help" => invocation.help = true,
+                _ => invocation.request.positional(arg)?,
+            }
+        }
+        Ok(invocation)
+    }
+
+    fn status_ok(&self, code: u16) -> bool {
+        if self.fail && code >= 400 {
+            return false;
+        }
+        self.expect.is_empty() || self.expect.iter().any(|pattern| pattern.matches(code))
+    }
+}
+
+/// `parsel send`: builds a request from flags alone.
+pub fn send(args: &[String]) -> ExitCode {
+    let invocation = match Invocation::parse(args) {
+        Ok(invocation) if invocation.help => return print_usage(&send_usage()),
+        Ok(invocation) => invocation,
+        Err(message) => return usage_error(&message, "send"),
+    };
+    let Some(url) = invocation.request.target.clone() else {
+        return usage_error("Missing URL", "send");
+    };
+    let mut spec = RequestSpec {
+        url,
+        ..Default::default()
+    };
+    invocation.request.apply(&mut spec);
+    execute(&invocation, &spec)
+}
+
+/// `parsel run`: sends a saved collection request.
+pub fn run(args: &[String]) -> ExitCode {
+    let invocation = match Invocation::parse(args) {
+        Ok(invocation) if invocation.help => return print_usage(&run_usage()),
+        Ok(invocation) => invocation,
+        Err(message) => return usage_error(&message, "run"),
+    };
+    let Some(id) = invocation.request.target.as_deref() else {
+        return usage_error("Missing saved request name", "run");
+    };
+    let collections = Collections::load();
+    let spec = CollectionItem::from_id(id).and_then(|item| {
+        if !collections.exists(&item) {
+            return Err(format!("No saved request named `{}`", item.id()));
+        }
+        collections.read(&item)
+    });
+    let mut spec = match spec {
+        Ok(spec) => spec,
+        Err(message) => return fail(&message, EXIT_USAGE),
+    };
+    invocation.request.apply(&mut spec);
+    execute(&invocation, &spec)
+}
+
+fn execute(invocation: &Invocation, spec: &RequestSpec) -> ExitCode {
+    let mut environments = match Environments::load() {
+        Ok(environments) => environments,
+        Err(message) => return fail(&message, EXIT_USAGE),
+    };
+    if let Some(name) = &invocation.request.env
+        && let Err(message) = environments.activate(name)
+    {
+        return fail(&message, EXIT_USAGE);
+    }
+    let prepared = match environments.prepare(spec) {
+        Ok(prepared) => prepared,
+        Err(message) => return fail(&message, EXIT_USAGE),
+    };
+    let mut cookies = match CookieJar::load() {
+        Ok(cookies) => cookies,
+        Err(message) => return fail(&message, EXIT_USAGE),
+    };
+
+    let started = Instant::now();
+    let received = match prepared.send(&cookies, &AtomicBool::new(false)) {
+        Ok(received) => received,
+        Err(err @ SendError::Invalid(_)) => return fail(&err.to_string(), EXIT_USAGE),
+        Err(err) => return fail(&err.to_string(), EXIT_TRANSPORT),
+    };
+    let elapsed = started.elapsed();
+    if cookies.store_received(&received, unix_timestamp()) > 0
+        && let Err(message) = cookies.save()
+    {
+        eprintln!("parsel: {message}");
+    }
+    let response = received.response;
+
+    let printed = if invocation.raw {
+        io::stdout().write_all(&response.body)
+    } else {
+        print_pretty(&response, elapsed.as_millis())
+    };
+    // A closed pipe (`parsel send … | head`) is not a failure.
+    if let Err(err) = printed.and_then(|()| io::stdout().flush())
+        && err.kind() != io::ErrorKind::BrokenPipe
+    {
+        return fail(
+            &format!("Failed to write the response: {err}"),
+            EXIT_TRANSPORT,
+        );
+    }
+
+    let code = response.status.as_u16();
+    if invocation.status_ok(code) {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("parsel: unexpected status {}", response.status);
+        ExitCode::from(EXIT_STATUS)
+    }
+}
+
+fn print_pretty(response: &Response, millis: u128) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    writeln!(out, "{} ({millis} ms)", response.status)?;
+    for header in &response.headers {
+        writeln!(out, "{}: {}", header.name, header.value)?;
+    }
+    if response.body.is_empty() {
+        return Ok(());
+    }
+    writeln!(out)?;
+    let content_type = response
+        .headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case("content-type"))
+        .map(|header| header.value.as_str());
+    if is_binary(content_type, &response.body) {
+        return writeln!(
+            out,
+            "[binary body, {}; use --raw to write the bytes]",
+            format_size(response.body.len())
+        );
+    }
+    let (body, _) = decode_body(content_type, &response.body, None);
+    let body = format_for_display(Some(&body), content_type).unwrap_or(body);
+    write!(out, "{body}")?;
+    if !body.ends_with('\n') {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn print_usage(usage: &str) -> ExitCode {
+    let _ = writeln!(io::stdout(), "{usage}");
+    ExitCode::SUCCESS
+}
+
+fn usage_error(message: &str, command: &str) -> ExitCode {
+    eprintln!("parsel: {message}\nRun `parsel {command} --help` for usage.");
+    ExitCode::from(EXIT_USAGE)
+}
+
+fn fail(message: &str, code: u8) -> ExitCode {
+    eprintln!("parsel: {message}");
+    ExitCode::from(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Invocation, StatusPattern};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn status_checks_follow_fail_and_expect() {
+        let invocation = Invocation::parse(&args("u --expect 2xx,304")).unwrap();
+        assert!(invocation.status_ok(204));
+        assert!(invocation.status_ok(304));
+        assert!(!invocation.status_ok(301));
+
+        let invocation = Invocation::parse(&args("u --fail")).unwrap();
+        assert!(invocation.status_ok(302));
+        assert!(!invocation.status_ok(404));
+
+        assert!(StatusPattern::parse("6xx").is_err());
+    }
+}
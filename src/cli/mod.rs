@@ -0,0 +1,85 @@
+pub mod args;
+mod headless;
+
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use crate::{
+    cli::args::{ArgCursor, REQUEST_FLAGS, RequestArgs},
+    ui::{self, Launch},
+};
+
+/// A status code failed `--fail` or `--expect`.
+pub const EXIT_STATUS: u8 = 1;
+/// Bad arguments, or a request that cannot be built.
+pub const EXIT_USAGE: u8 = 2;
+/// No response: connection failure, timeout or TLS error.
+pub const EXIT_TRANSPORT: u8 = 3;
+
+fn usage() -> String {
+    format!(
+        "Usage: parsel [OPTIONS] [URL]\n       \
+         parsel send [OPTIONS] <URL>\n       \
+         parsel run [OPTIONS] <folder/name>\n\n\
+         Opens the interactive client with the request panels filled from the flags.\n\
+         `send` and `run` send a request without the TUI; see `parsel send --help`.\n\n\
+         {REQUEST_FLAGS}\n\n\
+         Other:\n  \
+         \x20     --from-curl <CMD>    import a curl command; request flags apply on top\n  \
+         -h, --help               show this help\n  \
+         -V, --version            show the version"
+    )
+}
+
+/// Dispatches `parsel send` and `parsel run` to the headless mode and
+/// everything else to the TUI.
+pub fn run(args: Vec<String>) -> ExitCode {
+    match args.first().map(String::as_str) {
+        Some("send") => headless::send(&args[1..]),
+        Some("run") => headless::run(&args[1..]),
+        _ => {
+            let launch = match parse_launch(&args) {
+                Ok(Some(launch)) => launch,
+                Ok(None) => return ExitCode::SUCCESS,
+                Err(message) => {
+                    eprintln!("parsel: {message}\nRun `parsel --help` for usage.");
+                    return ExitCode::from(EXIT_USAGE);
+                }
+            };
+            match ui::run(launch) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(message) => {
+                    eprintln!("parsel: {message}");
+                    ExitCode::from(EXIT_USAGE)
+                }
+            }
+        }
+    }
+}
+
+/// Parses the TUI flags, or prints help/version and returns `None`.
+fn parse_launch(args: &[String]) -> Result<Option<Launch>, String> {
+    let mut request = RequestArgs::default();
+    let mut from_curl = None;
+    let mut cursor = ArgCursor::new(args);
+    while let Some(arg) = cursor.next_arg() {
+        if request.parse_flag(arg, &mut cursor)? {
+            continue;
+        }
+        match arg {
+            "--from-curl" => from_curl = Some(cursor.value(arg)?),
+            "-h" | "--help" => {
+                let _ = writeln!(io::stdout(), "{}", usage());
+                return Ok(None);
+            }
+            "-V" | "--version" => {
+                let _ = writeln!(io::stdout(), "parsel {}", env!("CARGO_PKG_VERSION"));
+                return Ok(None);
+            }
+            _ => request.positional(arg)?,
+        }
+    }
+    Ok(Some(Launch { request, from_curl }))
+}
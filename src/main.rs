@@ -1,5 +1,7 @@
-use parsel::ui;
+use std::{env, process::ExitCode};
 
-fn main() {
-    ui::run();
+use parsel::cli;
+
+fn main() -> ExitCode {
+    cli::run(env::args().skip(1).collect())
 }
@@ -1,14 +1,38 @@
 use std::{
-    borrow::Cow,
-    sync::mpsc::{Receiver, Sender},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, Sender},
+    },
     time::Instant,
 };
 
-use curl_rest::{Client, Header, Method, QueryParam, Response};
+use curl_rest::{Client, Method};
+use encoding_rs::Encoding;
 use ratatui::crossterm::event::KeyEvent;
+use serde::{Deserialize, Serialize};
 use tui_input::{Input, InputRequest};
 
+use crate::types::auth::{Auth, redact_secrets};
+use crate::types::charset::DetectedCharset;
+use crate::types::collection::{CollectionItem, Collections};
+use crate::types::cookies::CookieJar;
+use crate::types::environment::{Environments, substitute};
+use crate::types::export::ExportFormat;
+use crate::types::history::{History, HistoryEntry, unix_timestamp};
 use crate::types::input_handler::{InputHandler, InputState};
+use crate::types::json_body;
+use crate::types::json_filter::ResponseFilter;
+use crate::types::json_tree::JsonTree;
+use crate::types::multipart;
+use crate::types::oauth::token_status;
+use crate::types::prompt::Prompt;
+use crate::types::request::{
+    PreparedRequest, RequestSpec, parse_key_value_lines, pretty_json, validate_json,
+};
+use crate::types::search::ResponseSearch;
+use crate::types::send_options::SendOptions;
+use crate::types::transfer::Received;
 use crate::ui::sections::response_body::{ResponseBody, format_for_display};
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
@@ -18,11 +42,55 @@ pub enum Mode {
     Edit,
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BodyMode {
     #[default]
     Json,
     Form,
+    Multipart,
+    Raw,
+}
+
+/// Content type sent with a raw body. `Custom` leaves it to the
+/// `Content-Type` entry in the headers panel.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RawType {
+    #[default]
+    Json,
+    Xml,
+    Text,
+    Custom,
+}
+
+impl RawType {
+    pub fn content_type(self) -> Option<&'static str> {
+        match self {
+            Self::Json => Some("application/json"),
+            Self::Xml => Some("application/xml"),
+            Self::Text => Some("text/plain"),
+            Self::Custom => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Json => Self::Xml,
+            Self::Xml => Self::Text,
+            Self::Text => Self::Custom,
+            Self::Custom => Self::Json,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Self::Json => Self::Custom,
+            Self::Xml => Self::Json,
+            Self::Text => Self::Xml,
+            Self::Custom => Self::Text,
+        }
+    }
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
@@ -30,45 +98,70 @@ pub enum ActivePanel {
     #[default]
     Url,
     ReqQuery,
+    ReqOptions,
     ReqHeaders,
+    ReqAuth,
     ReqBody,
     ResHeaders,
     ResBody,
+    Collections,
 }
 
 impl ActivePanel {
     pub fn next(self) -> Self {
         match self {
             Self::Url => Self::ReqQuery,
-            Self::ReqQuery => Self::ReqHeaders,
-            Self::ReqHeaders => Self::ReqBody,
+            Self::ReqQuery => Self::ReqOptions,
+            Self::ReqOptions => Self::ReqHeaders,
+            Self::ReqHeaders => Self::ReqAuth,
+            Self::ReqAuth => Self::ReqBody,
             Self::ReqBody => Self::ResHeaders,
             Self::ResHeaders => Self::ResBody,
-            Self::ResBody => Self::Url,
+            Self::ResBody => Self::Collections,
+            Self::Collections => Self::Url,
         }
     }
 
     pub fn prev(self) -> Self {
         match self {
-            Self::Url => Self::ResBody,
+            Self::Url => Self::Collections,
             Self::ReqQuery => Self::Url,
-            Self::ReqHeaders => Self::ReqQuery,
-            Self::ReqBody => Self::ReqHeaders,
+            Self::ReqOptions => Self::ReqQuery,
+            Self::ReqHeaders => Self::ReqOptions,
+            Self::ReqAuth => Self::ReqHeaders,
+            Self::ReqBody => Self::ReqAuth,
             Self::ResHeaders => Self::ReqBody,
             Self::ResBody => Self::ResHeaders,
+            Self::Collections => Self::ResBody,
         }
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Overlay {
+    History,
+    Export,
+    Cookies,
+}
+
 #[derive(Debug, Default)]
 pub struct AppState {
     pub should_exit: bool,
     pub mode: Mode,
     pub active_panel: ActivePanel,
     pub body_mode: BodyMode,
+    pub raw_type: RawType,
     pub is_loading: bool,
     pub error: Option<String>,
+    /// Decoded body text, or the hex dump summary of a binary body.
     pub response_body: Option<String>,
+    /// The body exactly as received.
+    pub response_bytes: Option<Vec<u8>>,
+    pub response_binary: bool,
+    /// Encoding the body was decoded with; none for binary bodies.
+    pub response_charset: Option<DetectedCharset>,
+    /// Encoding forced from the charset prompt instead of detecting it.
+    pub charset_override: Option<&'static Encoding>,
     pub response_status: Option<String>,
     pub response_headers: Option<String>,
     pub response_content_type: Option<String>,
@@ -81,54 +174,107 @@ pub struct AppState {
     pub response_max_line_width: usize,
     pub response_max_line_width_cache: Option<usize>,
     pub response_time: u128,
+    /// Resolved URL of the last request sent, query string included.
+    pub request_url: Option<String>,
+    pub overlay: Option<Overlay>,
+    pub history_selected: usize,
+    pub history_filter_editing: bool,
+    pub cookie_selected: usize,
+    pub show_collections: bool,
+    pub collection_selected: usize,
+    pub status_message: Option<String>,
+    pub export_format: ExportFormat,
+    /// Multipart boundary shown in exported snippets, picked when the
+    /// export overlay opens so the snippet stays the same while it is open.
+    pub export_boundary: String,
+    pub search: ResponseSearch,
+    pub filter: ResponseFilter,
+    pub tree_view: bool,
+    pub response_tree: Option<JsonTree>,
+    /// Set by `y` until the next key picks what to copy.
+    pub yank_pending: bool,
+}
+
+/// The request currently being sent. Results tagged with another id belong
+/// to a cancelled request and are dropped.
+#[derive(Debug)]
+struct InFlight {
+    id: u64,
+    started: Instant,
+    cancelled: Arc<AtomicBool>,
+    history_entry: HistoryEntry,
 }
 
 #[derive(Debug, Default, Clone)]
 struct Request {
     method: Method,
-    body: String,
-}
-
-enum BodyPayload {
-    Json(String),
-    Form(String),
 }
 
 pub struct App<'a> {
     pub app_state: AppState,
     pub url_input: Input,
     pub req_query_input: Input,
+    pub req_options_input: Input,
     pub req_headers_input: Input,
+    pub req_auth_input: Input,
     pub req_body_input: Input,
+    pub history_filter_input: Input,
+    pub search_input: Input,
+    pub filter_input: Input,
+    pub history: History,
+    pub collections: Collections,
+    pub environments: Environments,
+    pub cookies: CookieJar,
+    pub loaded_collection_item: Option<CollectionItem>,
+    pub prompt: Option<Prompt>,
     pub network: Client<'a>,
     request: Request,
-    request_tx: Sender<Response>,
-    request_rx: Receiver<Response>,
-    his_tx: Sender<Request>,
-    err_tx: Sender<String>,
-    err_rx: Receiver<String>,
-    elapsed_tx: Sender<u128>,
-    elapsed_rx: Receiver<u128>,
+    next_request_id: u64,
+    in_flight: Option<InFlight>,
+    cancelled_requests: Vec<u64>,
+    request_tx: Sender<(u64, Received)>,
+    request_rx: Receiver<(u64, Received)>,
+    his_tx: Sender<(u64, HistoryEntry)>,
+    his_rx: Receiver<(u64, HistoryEntry)>,
+    err_tx: Sender<(u64, String)>,
+    err_rx: Receiver<(u64, String)>,
+    elapsed_tx: Sender<(u64, u128)>,
+    elapsed_rx: Receiver<(u64, u128)>,
 }
 
 impl<'a> Default for App<'a> {
     fn default() -> Self {
-        let (request_tx, request_rx) = std::sync::mpsc::channel::<Response>();
-        let (his_tx, _his_rx) = std::sync::mpsc::channel::<Request>();
-        let (err_tx, err_rx) = std::sync::mpsc::channel::<String>();
-        let (elapsed_tx, elapsed_rx) = std::sync::mpsc::channel::<u128>();
+        let (request_tx, request_rx) = std::sync::mpsc::channel::<(u64, Received)>();
+        let (his_tx, his_rx) = std::sync::mpsc::channel::<(u64, HistoryEntry)>();
+        let (err_tx, err_rx) = std::sync::mpsc::channel::<(u64, String)>();
+        let (elapsed_tx, elapsed_rx) = std::sync::mpsc::channel::<(u64, u128)>();
 
         Self {
             app_state: Default::default(),
             url_input: Default::default(),
             req_query_input: Default::default(),
+            req_options_input: Default::default(),
             req_headers_input: Default::default(),
+            req_auth_input: Default::default(),
             req_body_input: Default::default(),
+            history_filter_input: Default::default(),
+            search_input: Default::default(),
+            filter_input: Default::default(),
+            history: Default::default(),
+            collections: Default::default(),
+            environments: Default::default(),
+            cookies: Default::default(),
+            loaded_collection_item: None,
+            prompt: None,
             network: Default::default(),
             request: Default::default(),
+            next_request_id: 0,
+            in_flight: None,
+            cancelled_requests: Vec::new(),
             request_tx,
             request_rx,
             his_tx,
+            his_rx,
             err_tx,
             err_rx,
             elapsed_tx,
@@ -138,10 +284,23 @@ impl<'a> Default for App<'a> {
 }
 
 impl<'a> App<'a> {
-    pub(crate) fn with_default_url(mut self, url: &str) -> Self {
-        url.chars().for_each(|char| {
-            self.url_input.handle(InputRequest::InsertChar(char));
-        });
+    pub(crate) fn with_history(mut self, history: History) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub(crate) fn with_collections(mut self, collections: Collections) -> Self {
+        self.collections = collections;
+        self
+    }
+
+    pub(crate) fn with_environments(mut self, environments: Environments) -> Self {
+        self.environments = environments;
+        self
+    }
+
+    pub(crate) fn with_cookies(mut self, cookies: CookieJar) -> Self {
+        self.cookies = cookies;
         self
     }
 
@@ -150,20 +309,34 @@ impl<'a> App<'a> {
         input_handler.handle(key_event);
     }
 
-    pub(crate) fn method_label(&self) -> &'static str {
-        match self.request.method {
-            Method::Get => "GET",
-            Method::Post => "POST",
-            Method::Put => "PUT",
-            Method::Delete => "DELETE",
-            Method::Patch => "PATCH",
-            Method::Head => "HEAD",
-            Method::Options => "OPTIONS",
-            Method::Connect => "CONNECT",
-            Method::Trace => "TRACE",
+    /// Inserts bracketed-paste text into the prompt or the panel being edited.
+    pub(crate) fn handle_paste(&mut self, text: &str) {
+        if self.prompt.is_some() {
+            self.paste_into_prompt(text);
+            return;
+        }
+        if self.app_state.mode != Mode::Edit {
+            return;
+        }
+        let (input, multiline) = match self.app_state.active_panel {
+            ActivePanel::Url => (&mut self.url_input, false),
+            ActivePanel::ReqQuery => (&mut self.req_query_input, true),
+            ActivePanel::ReqOptions => (&mut self.req_options_input, true),
+            ActivePanel::ReqHeaders => (&mut self.req_headers_input, true),
+            ActivePanel::ReqAuth => (&mut self.req_auth_input, true),
+            ActivePanel::ReqBody => (&mut self.req_body_input, true),
+            _ => return,
+        };
+        for ch in text.chars().filter(|ch| *ch != '\r') {
+            let ch = if ch == '\n' && !multiline { ' ' } else { ch };
+            input.handle(InputRequest::InsertChar(ch));
         }
     }
 
+    pub(crate) fn method_label(&self) -> &'static str {
+        method_label(&self.request.method)
+    }
+
     pub(crate) fn next_method(&mut self) {
         self.request.method = match self.request.method {
             Method::Get => Method::Post,
@@ -192,31 +365,200 @@ impl<'a> App<'a> {
         };
     }
 
-    pub(crate) fn body_content_type(&self) -> &'static str {
+    pub(crate) fn body_content_type(&self) -> String {
         match self.app_state.body_mode {
-            BodyMode::Json => "application/json",
-            BodyMode::Form => "application/x-www-form-urlencoded",
+            BodyMode::Json => "application/json".to_string(),
+            BodyMode::Form => "application/x-www-form-urlencoded".to_string(),
+            BodyMode::Multipart => "multipart/form-data".to_string(),
+            BodyMode::Raw => {
+                let headers = parse_key_value_lines(self.req_headers_input.value());
+                let content_type = headers
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                    .map(|(_, value)| value.as_str());
+                match (self.app_state.raw_type.content_type(), content_type) {
+                    (_, Some(content_type)) => format!("raw: {content_type}"),
+                    (Some(content_type), None) => format!("raw: {content_type}"),
+                    (None, None) => "raw: set a Content-Type header".to_string(),
+                }
+            }
         }
     }
 
     pub(crate) fn next_body_mode(&mut self) {
         self.app_state.body_mode = match self.app_state.body_mode {
             BodyMode::Json => BodyMode::Form,
-            BodyMode::Form => BodyMode::Json,
+            BodyMode::Form => BodyMode::Multipart,
+            BodyMode::Multipart => BodyMode::Raw,
+            BodyMode::Raw => BodyMode::Json,
         };
     }
 
     pub(crate) fn prev_body_mode(&mut self) {
         self.app_state.body_mode = match self.app_state.body_mode {
-            BodyMode::Json => BodyMode::Form,
+            BodyMode::Json => BodyMode::Raw,
             BodyMode::Form => BodyMode::Json,
+            BodyMode::Multipart => BodyMode::Form,
+            BodyMode::Raw => BodyMode::Multipart,
         };
     }
 
+    pub(crate) fn next_raw_type(&mut self) {
+        if self.app_state.body_mode == BodyMode::Raw {
+            self.app_state.raw_type = self.app_state.raw_type.next();
+        }
+    }
+
+    pub(crate) fn prev_raw_type(&mut self) {
+        if self.app_state.body_mode == BodyMode::Raw {
+            self.app_state.raw_type = self.app_state.raw_type.prev();
+        }
+    }
+
+    /// Whether the body panel holds a raw body that should be valid JSON.
+    pub(crate) fn is_raw_json(&self) -> bool {
+        self.app_state.body_mode == BodyMode::Raw && self.app_state.raw_type == RawType::Json
+    }
+
+    /// Body check shown under the body panel, after variable substitution:
+    /// the parse result for raw JSON, and key errors for the JSON builder.
+    pub(crate) fn body_validation(&self) -> Option<Result<(), String>> {
+        let (body, _) = substitute(
+            self.req_body_input.value(),
+            self.environments.active_variables(),
+        );
+        match self.app_state.body_mode {
+            BodyMode::Raw if self.app_state.raw_type == RawType::Json => Some(validate_json(&body)),
+            BodyMode::Json => json_body::build(parse_key_value_lines(&body))
+                .err()
+                .map(Err),
+            BodyMode::Multipart => {
+                multipart::validate(&multipart::parse_parts(parse_key_value_lines(&body)))
+                    .err()
+                    .map(Err)
+            }
+            _ => None,
+        }
+    }
+
+    /// Why the options panel cannot be parsed, after variable substitution.
+    pub(crate) fn options_validation(&self) -> Option<String> {
+        let (options, _) = substitute(
+            self.req_options_input.value(),
+            self.environments.active_variables(),
+        );
+        SendOptions::parse(&options).err()
+    }
+
+    /// Why the auth panel cannot be parsed, after variable substitution.
+    pub(crate) fn auth_validation(&self) -> Option<String> {
+        let (auth, _) = substitute(
+            self.req_auth_input.value(),
+            self.environments.active_variables(),
+        );
+        Auth::parse(&auth).err()
+    }
+
+    /// State of the cached OAuth 2.0 token when the auth panel uses one.
+    pub(crate) fn auth_status(&self) -> Option<String> {
+        let (auth, _) = substitute(
+            self.req_auth_input.value(),
+            self.environments.active_variables(),
+        );
+        match Auth::parse(&auth) {
+            Ok(Auth::OAuth2(config)) => Some(token_status(&config).label()),
+            _ => None,
+        }
+    }
+
+    /// For each line of a multipart body, the size of the file it uploads or
+    /// why the file cannot be read; `None` for text fields and other modes.
+    pub(crate) fn multipart_file_notes(&self) -> Vec<Option<Result<u64, String>>> {
+        if self.app_state.body_mode != BodyMode::Multipart {
+            return Vec::new();
+        }
+        let vars = self.environments.active_variables();
+        self.req_body_input
+            .value()
+            .split('\n')
+            .map(|line| {
+                let (line, _) = substitute(line, vars);
+                let (name, value) = parse_key_value_lines(&line).pop()?;
+                match multipart::parse_part(&name, &value) {
+                    multipart::Part::File { path, .. } => Some(multipart::check_file(&path)),
+                    multipart::Part::Text { .. } => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Re-indents a raw JSON body in place, keeping key order.
+    pub(crate) fn format_raw_body(&mut self) {
+        if !self.is_raw_json() {
+            return;
+        }
+        match pretty_json(self.req_body_input.value()) {
+            Ok(formatted) => self.req_body_input = Input::new(formatted),
+            Err(message) => self.app_state.error = Some(message),
+        }
+    }
+
+    /// Moves focus to the next panel, skipping the sidebar when it is hidden.
+    pub(crate) fn focus_next_panel(&mut self) {
+        let mut panel = self.app_state.active_panel.next();
+        if panel == ActivePanel::Collections && !self.app_state.show_collections {
+            panel = panel.next();
+        }
+        self.app_state.active_panel = panel;
+    }
+
+    pub(crate) fn focus_prev_panel(&mut self) {
+        let mut panel = self.app_state.active_panel.prev();
+        if panel == ActivePanel::Collections && !self.app_state.show_collections {
+            panel = panel.prev();
+        }
+        self.app_state.active_panel = panel;
+    }
+
+    pub(crate) fn next_environment(&mut self) {
+        self.environments.next();
+        self.app_state.status_message = Some(self.environment_message());
+    }
+
+    pub(crate) fn prev_environment(&mut self) {
+        self.environments.prev();
+        self.app_state.status_message = Some(self.environment_message());
+    }
+
+    fn environment_message(&self) -> String {
+        match self.environments.active_name() {
+            Some(name) => format!("Environment: {name}"),
+            None => "No environment".to_string(),
+        }
+    }
+
+    /// The current request with environment variables resolved, as it would
+    /// be sent.
+    pub(crate) fn prepared_request(&self) -> Result<PreparedRequest, String> {
+        self.environments.prepare(&self.request_spec())
+    }
+
     pub(crate) fn send_request(&mut self) {
+        let prepared = match self.prepared_request() {
+            Ok(prepared) => prepared,
+            Err(message) => {
+                self.app_state.error = Some(message);
+                return;
+            }
+        };
+
         self.app_state.is_loading = true;
         self.app_state.error = None;
+        self.app_state.status_message = None;
         self.app_state.response_body = None;
+        self.app_state.response_bytes = None;
+        self.app_state.response_binary = false;
+        self.app_state.response_charset = None;
         self.app_state.response_status = None;
         self.app_state.response_headers = None;
         self.app_state.response_content_type = None;
@@ -224,116 +566,108 @@ impl<'a> App<'a> {
         self.app_state.response_max_line_width_cache = None;
         self.app_state.response_scroll = 0;
         self.app_state.response_scroll_x = 0;
+        self.app_state.request_url = Some(prepared.full_url());
         self.refresh_response_body_cache();
-
-        let request = self.request.clone();
-        let method = self.request.method.clone();
-        let url = self.url_input.value().to_string();
-        let _body = self.request.body.to_string();
+        self.rebuild_response_tree();
+
+        let mut history_entry = self.history_entry();
+        self.next_request_id += 1;
+        let id = self.next_request_id;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.in_flight = Some(InFlight {
+            id,
+            started: Instant::now(),
+            cancelled: cancelled.clone(),
+            history_entry: history_entry.clone(),
+        });
         let request_tx = self.request_tx.clone();
         let his_tx = self.his_tx.clone();
         let error_tx = self.err_tx.clone();
         let elapsed_tx = self.elapsed_tx.clone();
-
-        let query_params = parse_query_params(self.req_query_input.value());
-        let mut headers = parse_headers(self.req_headers_input.value());
-        let body_mode = self.app_state.body_mode;
-        let body_raw = self.req_body_input.value().to_string();
-        let body_payload = match body_mode {
-            BodyMode::Json => {
-                let pairs = parse_key_value_lines(&body_raw);
-                let json = json_from_pairs(pairs);
-                json.map(BodyPayload::Json)
-            }
-            BodyMode::Form => {
-                let pairs = parse_key_value_lines(&body_raw);
-                let encoded = form_encode(pairs);
-                if encoded.is_empty() {
-                    None
-                } else {
-                    Some(BodyPayload::Form(encoded))
-                }
-            }
-        };
-
-        if matches!(body_payload, Some(BodyPayload::Form(_))) && !has_content_type(&headers) {
-            headers.push(Header::ContentType(Cow::Borrowed(
-                "application/x-www-form-urlencoded",
-            )));
-        }
-
-        let needs_brotli = should_enable_brotli(&headers);
+        let cookies = self.cookies.clone();
 
         std::thread::spawn(move || {
             let start_time = Instant::now();
-            let mut http_client: Client<'static> = Client::default()
-                .query_params(query_params)
-                .brotli(needs_brotli)
-                .headers(headers);
-            if let Some(body_payload) = body_payload {
-                http_client = match body_payload {
-                    BodyPayload::Json(body) => http_client.body_json(body),
-                    BodyPayload::Form(body) => http_client.body_text(body),
-                };
-            }
-            let res = match method {
-                Method::Get => http_client.get().send(url.as_str()),
-                Method::Post => http_client.post().send(url.as_str()),
-                Method::Put => http_client.put().send(url.as_str()),
-                Method::Delete => http_client.delete().send(url.as_str()),
-                Method::Patch => http_client.patch().send(url.as_str()),
-                Method::Head => http_client.head().send(url.as_str()),
-                Method::Options => http_client.options().send(url.as_str()),
-                Method::Connect => http_client.connect().send(url.as_str()),
-                Method::Trace => http_client.trace().send(url.as_str()),
-            };
-
+            let res = prepared.send(&cookies, &cancelled);
             let elapsed = start_time.elapsed().as_millis();
 
             match res {
-                Ok(res) => {
-                    let resp = Response {
-                        body: res.body,
-                        status: res.status,
-                        headers: res.headers,
-                    };
-                    let _ = request_tx.send(resp);
-                    let _ = his_tx.send(request);
-                    let _ = elapsed_tx.send(elapsed);
+                Ok(received) => {
+                    history_entry.status = Some(received.response.status.as_u16());
+                    history_entry.elapsed_ms = elapsed as u64;
+                    history_entry.response_size = received.response.body.len();
+                    let _ = elapsed_tx.send((id, elapsed));
+                    let _ = request_tx.send((id, received));
+                    let _ = his_tx.send((id, history_entry));
                 }
                 Err(err) => {
-                    let message = match err {
-                        curl_rest::Error::Client(error) => {
-                            format!("Curl error {}", error)
-                        }
-                        curl_rest::Error::InvalidUrl(url) => {
-                            format!("The provided url: {url} is invalid.")
-                        }
-                        curl_rest::Error::InvalidHeaderValue(v) => {
-                            format!("Invalid header value: {v} supplied.")
-                        }
-                        curl_rest::Error::InvalidHeaderName(n) => {
-                            format!("Invalid header name: {n}.")
-                        }
-                        curl_rest::Error::InvalidStatusCode(c) => {
-                            format!("Invalid status code: {c}, supplied.")
-                        }
-                        curl_rest::Error::BrotliDecompression(e) => {
-                            format!("Failed to deceompress brotli: {e}")
-                        }
-                    };
-                    let _ = error_tx.send(message.to_string());
+                    let message = err.to_string();
+                    history_entry.elapsed_ms = elapsed as u64;
+                    history_entry.error = Some(message.clone());
+                    let _ = error_tx.send((id, message.to_string()));
+                    let _ = his_tx.send((id, history_entry));
                 }
             }
         });
     }
 
+    /// Stops the in-flight request. The worker thread aborts the transfer
+    /// within a second, skipping any retries, and what it sends back is
+    /// discarded.
+    pub(crate) fn cancel_request(&mut self) {
+        let Some(in_flight) = self.in_flight.take() else {
+            return;
+        };
+        in_flight.cancelled.store(true, Ordering::Relaxed);
+        let elapsed = in_flight.started.elapsed();
+        let mut entry = in_flight.history_entry;
+        entry.elapsed_ms = elapsed.as_millis() as u64;
+        entry.error = Some("Cancelled".to_string());
+        self.app_state.status_message = Some(format!(
+            "Cancelled {} {} after {:.1}s",
+            entry.request.method,
+            entry.request.url,
+            elapsed.as_secs_f64()
+        ));
+        self.history.record(entry);
+        self.cancelled_requests.push(in_flight.id);
+        self.app_state.is_loading = false;
+    }
+
+    fn is_in_flight(&self, id: u64) -> bool {
+        self.in_flight
+            .as_ref()
+            .is_some_and(|in_flight| in_flight.id == id)
+    }
+
     pub(crate) fn poll_network(&mut self) {
-        if let Ok(response) = self.request_rx.try_recv() {
+        while let Ok((id, entry)) = self.his_rx.try_recv() {
+            match self
+                .cancelled_requests
+                .iter()
+                .position(|cancelled| *cancelled == id)
+            {
+                Some(idx) => {
+                    self.cancelled_requests.remove(idx);
+                }
+                None => self.history.record(entry),
+            }
+        }
+
+        if let Ok((id, request_time)) = self.elapsed_rx.try_recv()
+            && self.is_in_flight(id)
+        {
+            self.app_state.response_time = request_time
+        }
+
+        if let Ok((id, received)) = self.request_rx.try_recv()
+            && self.is_in_flight(id)
+        {
+            self.in_flight = None;
             self.app_state.is_loading = false;
+            self.store_cookies(&received);
+            let response = received.response;
             self.app_state.response_status = Some(response.status.to_string());
-            self.app_state.response_body =
-                Some(String::from_utf8_lossy(&response.body).to_string());
             self.app_state.response_content_type = response
                 .headers
                 .iter()
@@ -351,144 +685,158 @@ impl<'a> App<'a> {
                     .join("\n");
                 self.app_state.response_headers = Some(headers);
             }
+            self.app_state.response_bytes = Some(response.body);
+            self.decode_response();
             self.app_state.response_scroll = 0;
             self.app_state.response_scroll_x = 0;
             self.refresh_response_body_cache();
+            self.rebuild_response_tree();
             return;
         }
 
-        if let Ok(message) = self.err_rx.try_recv() {
+        if let Ok((id, message)) = self.err_rx.try_recv()
+            && self.is_in_flight(id)
+        {
+            self.in_flight = None;
             self.app_state.is_loading = false;
             self.app_state.error = Some(message);
         }
+    }
 
-        if let Ok(request_time) = self.elapsed_rx.try_recv() {
-            self.app_state.response_time = request_time
+    /// History entry for the request being sent, with the secrets of the
+    /// auth panel redacted.
+    fn history_entry(&self) -> HistoryEntry {
+        let mut request = self.request_spec();
+        request.auth = redact_secrets(&request.auth);
+        HistoryEntry {
+            timestamp: unix_timestamp(),
+            request,
+            ..Default::default()
         }
     }
 
-    fn refresh_response_body_cache(&mut self) {
-        let body = self.app_state.response_body.as_deref();
-        let content_type = self.app_state.response_content_type.as_deref();
-        self.app_state.response_formatted_body = format_for_display(body, content_type);
-        let formatted = self.app_state.response_formatted_body.as_deref();
-        self.app_state.response_line_count = ResponseBody.line_count(body, formatted, content_type);
-        let max_line_width = ResponseBody.max_line_width(body, formatted, content_type, None);
-        self.app_state.response_max_line_width = max_line_width;
-        self.app_state.response_max_line_width_cache = Some(max_line_width);
+    /// Snapshot of the request panels.
+    pub(crate) fn request_spec(&self) -> RequestSpec {
+        RequestSpec {
+            method: self.method_label().to_string(),
+            url: self.url_input.value().to_string(),
+            body_mode: self.app_state.body_mode,
+            raw_type: self.app_state.raw_type,
+            query: self.req_query_input.value().to_string(),
+            headers: self.req_headers_input.value().to_string(),
+            body: self.req_body_input.value().to_string(),
+            options: self.req_options_input.value().to_string(),
+            auth: self.req_auth_input.value().to_string(),
+        }
     }
-}
-
-fn parse_key_value_lines(input: &str) -> Vec<(String, String)> {
-    input
-        .lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .filter_map(|line| {
-            let (key, value) = line.split_once(':')?;
-            let key = key.trim();
-            if key.is_empty() {
-                return None;
-            }
-            let value = value.trim();
-            Some((key.to_string(), value.to_string()))
-        })
-        .collect()
-}
 
-fn form_encode(pairs: Vec<(String, String)>) -> String {
-    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
-    for (key, value) in pairs {
-        serializer.append_pair(&key, &value);
+    /// Replaces the request panels with the contents of `spec`.
+    pub(crate) fn apply_request_spec(&mut self, spec: RequestSpec) {
+        if let Some(method) = method_from_label(&spec.method) {
+            self.request.method = method;
+        }
+        self.url_input = Input::new(spec.url);
+        self.req_query_input = Input::new(spec.query);
+        self.req_headers_input = Input::new(spec.headers);
+        self.req_body_input = Input::new(spec.body);
+        self.req_options_input = Input::new(spec.options);
+        self.req_auth_input = Input::new(spec.auth);
+        self.app_state.body_mode = spec.body_mode;
+        self.app_state.raw_type = spec.raw_type;
     }
-    serializer.finish()
-}
 
-fn json_from_pairs(pairs: Vec<(String, String)>) -> Option<String> {
-    if pairs.is_empty() {
-        return None;
-    }
-    let mut map = serde_json::Map::new();
-    for (key, value) in pairs {
-        map.insert(key, serde_json::Value::String(value));
+    /// Indices into `history` of the entries shown in the history overlay.
+    pub(crate) fn visible_history(&self) -> Vec<usize> {
+        self.history.filtered(self.history_filter_input.value())
     }
-    serde_json::to_string(&serde_json::Value::Object(map)).ok()
-}
-
-fn parse_query_params(input: &str) -> Vec<QueryParam<'static>> {
-    parse_key_value_lines(input)
-        .into_iter()
-        .map(|(key, value)| QueryParam::new(key, value))
-        .collect()
-}
 
-fn parse_headers(input: &str) -> Vec<Header<'static>> {
-    parse_key_value_lines(input)
-        .into_iter()
-        .map(|(key, value)| Header::Custom(Cow::Owned(key), Cow::Owned(value)))
-        .collect()
-}
-
-fn has_content_type(headers: &[Header<'static>]) -> bool {
-    headers.iter().any(|header| match header {
-        Header::ContentType(_) => true,
-        Header::Custom(name, _) => name.eq_ignore_ascii_case("content-type"),
-        _ => false,
-    })
-}
+    pub(crate) fn open_history(&mut self) {
+        self.app_state.overlay = Some(Overlay::History);
+        self.app_state.history_selected = 0;
+        self.app_state.history_filter_editing = false;
+    }
 
-fn should_enable_brotli(headers: &[Header<'static>]) -> bool {
-    let mut best_encoding: Option<(&str, f32, usize)> = None;
-    let mut index = 0usize;
+    pub(crate) fn close_overlay(&mut self) {
+        self.app_state.overlay = None;
+        self.app_state.history_filter_editing = false;
+    }
 
-    for header in headers {
-        let Header::Custom(key, value) = header else {
-            continue;
-        };
-        if !key.eq_ignore_ascii_case("accept-encoding") {
-            continue;
+    pub(crate) fn select_next_history(&mut self) {
+        let count = self.visible_history().len();
+        if self.app_state.history_selected + 1 < count {
+            self.app_state.history_selected += 1;
         }
+    }
 
-        for token in value.split(',') {
-            let mut parts = token.split(';');
-            let encoding = parts.next().map(str::trim).unwrap_or_default();
-            if encoding.is_empty() {
-                continue;
-            }
+    pub(crate) fn select_prev_history(&mut self) {
+        self.app_state.history_selected = self.app_state.history_selected.saturating_sub(1);
+    }
 
-            let mut q = 1.0f32;
-            for param in parts {
-                let Some((name, raw_value)) = param.split_once('=') else {
-                    continue;
-                };
-                if !name.trim().eq_ignore_ascii_case("q") {
-                    continue;
-                }
+    /// Loads the selected history entry back into the request panels.
+    pub(crate) fn load_selected_history(&mut self) {
+        let visible = self.visible_history();
+        let Some(entry) = visible
+            .get(self.app_state.history_selected)
+            .and_then(|idx| self.history.entries().get(*idx))
+            .cloned()
+        else {
+            return;
+        };
 
-                let Ok(parsed) = raw_value.trim().parse::<f32>() else {
-                    q = -1.0;
-                    break;
-                };
-                if !parsed.is_finite() || !(0.0..=1.0).contains(&parsed) {
-                    q = -1.0;
-                    break;
-                }
-                q = parsed;
-                break;
-            }
+        self.apply_request_spec(entry.request);
+        self.close_overlay();
+    }
 
-            if q < 0.0 {
-                continue;
-            }
+    pub(crate) fn refresh_response_body_cache(&mut self) {
+        self.refresh_filter();
+        self.refresh_response_view();
+    }
 
-            match best_encoding {
-                Some((_, best_q, best_idx)) if q < best_q || (q == best_q && index > best_idx) => {}
-                _ => best_encoding = Some((encoding, q, index)),
-            }
+    /// Reformats the body, or the filter output, for display.
+    pub(crate) fn refresh_response_view(&mut self) {
+        let body = self.app_state.response_body.as_deref();
+        let content_type = self.app_state.response_content_type.as_deref();
+        self.app_state.response_formatted_body = match self.filtered_body() {
+            Some(filtered) => Some(filtered.to_string()),
+            None if self.app_state.response_binary => None,
+            None => format_for_display(body, content_type),
+        };
+        let body = self.app_state.response_body.as_deref();
+        let content_type = self.app_state.response_content_type.as_deref();
+        let formatted = self.app_state.response_formatted_body.as_deref();
+        self.app_state.response_line_count = ResponseBody.line_count(body, formatted, content_type);
+        let max_line_width = ResponseBody.max_line_width(body, formatted, content_type, None);
+        self.app_state.response_max_line_width = max_line_width;
+        self.app_state.response_max_line_width_cache = Some(max_line_width);
+        self.refresh_search();
+    }
+}
 
-            index += 1;
-        }
+pub(crate) fn method_label(method: &Method) -> &'static str {
+    match method {
+        Method::Get => "GET",
+        Method::Post => "POST",
+        Method::Put => "PUT",
+        Method::Delete => "DELETE",
+        Method::Patch => "PATCH",
+        Method::Head => "HEAD",
+        Method::Options => "OPTIONS",
+        Method::Connect => "CONNECT",
+        Method::Trace => "TRACE",
     }
+}
 
-    matches!(best_encoding, Some((encoding, q, _)) if q > 0.0 && encoding.eq_ignore_ascii_case("br"))
+pub(crate) fn method_from_label(label: &str) -> Option<Method> {
+    match label.trim().to_ascii_uppercase().as_str() {
+        "GET" => Some(Method::Get),
+        "POST" => Some(Method::Post),
+        "PUT" => Some(Method::Put),
+        "DELETE" => Some(Method::Delete),
+        "PATCH" => Some(Method::Patch),
+        "HEAD" => Some(Method::Head),
+        "OPTIONS" => Some(Method::Options),
+        "CONNECT" => Some(Method::Connect),
+        "TRACE" => Some(Method::Trace),
+        _ => None,
+    }
 }
@@ -0,0 +1,580 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use md5::{Digest as _, Md5};
+use sha2::Sha256;
+
+use crate::types::{
+    environment::find_placeholders,
+    hmac_signer::{DEFAULT_TEMPLATE, HmacAlgorithm, HmacConfig, SignatureEncoding, parse_template},
+    oauth::{ClientAuth, Grant, OAuthConfig},
+    request::{has_header, parse_key_value_lines},
+    sigv4::SigV4Config,
+};
+
+/// Keys accepted by the auth panel, shown as its placeholder.
+pub const AUTH_KEYS: &[&str] = &[
+    "type: basic | bearer | api_key | digest | oauth2 | aws_sigv4 | hmac",
+    "username: alice",
+    "password: {{password}}",
+    "token: {{token}}",
+    "name: X-API-Key",
+    "value: {{api_key}}",
+    "in: header | query",
+    "grant: client_credentials | password | refresh_token",
+    "token_url: https://auth.example.com/token",
+    "client_id: parsel",
+    "client_secret: {{client_secret}}",
+    "scope: read write",
+    "access_key: {{aws_access_key_id}}",
+    "secret_key: {{aws_secret_access_key}}",
+    "region: us-east-1",
+    "service: s3",
+    "secret: {{hmac_secret}}",
+    "header: X-Signature",
+    "template: {timestamp}{method}{path}{body}",
+];
+
+const KNOWN_KEYS: &[&str] = &[
+    "type",
+    "username",
+    "password",
+    "token",
+    "name",
+    "value",
+    "in",
+    "grant",
+    "token_url",
+    "client_id",
+    "client_secret",
+    "client_auth",
+    "scope",
+    "refresh_token",
+    "access_key",
+    "secret_key",
+    "session_token",
+    "region",
+    "service",
+    "secret",
+    "algorithm",
+    "encoding",
+    "header",
+    "prefix",
+    "template",
+    "timestamp_header",
+];
+
+const SCHEMES: &str = "basic, bearer, api_key, digest, oauth2, aws_sigv4 or hmac";
+
+/// Keys whose values are masked on screen and in history.
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "token",
+    "value",
+    "client_secret",
+    "refresh_token",
+    "secret_key",
+    "session_token",
+    "secret",
+];
+
+/// What a secret is replaced with in history.
+const REDACTED: &str = "••••••";
+
+/// Where an API key is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLocation {
+    Header,
+    Query,
+}
+
+/// Authentication scheme, parsed from the auth panel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Auth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    ApiKey {
+        name: String,
+        value: String,
+        location: KeyLocation,
+    },
+    /// Answered when the server replies 401 with a `Digest` challenge.
+    Digest {
+        username: String,
+        password: String,
+    },
+    /// A bearer token from an OAuth 2.0 token endpoint, fetched when the
+    /// request is sent.
+    OAuth2(OAuthConfig),
+    /// AWS Signature Version 4, computed over the final request.
+    AwsSigV4(SigV4Config),
+    /// An HMAC over a canonical string template, computed over the final
+    /// request.
+    Hmac(HmacConfig),
+}
+
+impl Auth {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if !line.contains(':') {
+                return Err(format!("Expected `key: value`, got `{line}`"));
+            }
+        }
+        let mut fields = HashMap::new();
+        for (key, value) in parse_key_value_lines(input) {
+            let key = normalize_key(&key);
+            if !KNOWN_KEYS.contains(&key.as_str()) {
+                return Err(format!("Unknown auth key `{key}`"));
+            }
+            fields.insert(key, value);
+        }
+        let Some(kind) = fields.remove("type") else {
+            return if fields.is_empty() {
+                Ok(Self::None)
+            } else {
+                Err(format!("Set `type` to {SCHEMES}"))
+            };
+        };
+        let mut take = |key: &str| fields.remove(key).unwrap_or_default();
+        let required = |value: String, scheme: &str, key: &str| {
+            if value.is_empty() {
+                Err(format!("{scheme} auth needs a `{key}`"))
+            } else {
+                Ok(value)
+            }
+        };
+        Ok(match kind.to_ascii_lowercase().replace('-', "_").as_str() {
+            "none" => Self::None,
+            "basic" => Self::Basic {
+                username: required(take("username"), "Basic", "username")?,
+                password: take("password"),
+            },
+            "bearer" => Self::Bearer {
+                token: required(take("token"), "Bearer", "token")?,
+            },
+            "api_key" | "apikey" => {
+                let location = match take("in").to_ascii_lowercase().as_str() {
+                    "" | "header" => KeyLocation::Header,
+                    "query" => KeyLocation::Query,
+                    other => return Err(format!("`in` expects header or query, got `{other}`")),
+                };
+                Self::ApiKey {
+                    name: required(take("name"), "API key", "name")?,
+                    value: take("value"),
+                    location,
+                }
+            }
+            "digest" => Self::Digest {
+                username: required(take("username"), "Digest", "username")?,
+                password: take("password"),
+            },
+            "oauth2" => {
+                let grant = match take("grant")
+                    .to_ascii_lowercase()
+                    .replace('-', "_")
+                    .as_str()
+                {
+                    "" | "client_credentials" => Grant::ClientCredentials,
+                    "password" => Grant::Password {
+                        username: required(take("username"), "OAuth2 password", "username")?,
+                        password: take("password"),
+                    },
+                    "refresh_token" => Grant::RefreshToken {
+                        refresh_token: required(
+                            take("refresh_token"),
+                            "OAuth2 refresh_token",
+                            "refresh_token",
+                        )?,
+                    },
+                    other => {
+                        return Err(format!(
+                            "Unknown grant `{other}`; use client_credentials, password or refresh_token"
+                        ));
+                    }
+                };
+                let client_auth = match take("client_auth").to_ascii_lowercase().as_str() {
+                    "" | "basic" => ClientAuth::Basic,
+                    "body" => ClientAuth::Body,
+                    other => {
+                        return Err(format!(
+                            "`client_auth` expects basic or body, got `{other}`"
+                        ));
+                    }
+                };
+                let scope = take("scope");
+                Self::OAuth2(OAuthConfig {
+                    token_url: required(take("token_url"), "OAuth2", "token_url")?,
+                    client_id: required(take("client_id"), "OAuth2", "client_id")?,
+                    client_secret: take("client_secret"),
+                    scope: (!scope.is_empty()).then_some(scope),
+                    grant,
+                    client_auth,
+                })
+            }
+            "aws_sigv4" | "sigv4" | "aws" => {
+                let session_token = take("session_token");
+                Self::AwsSigV4(SigV4Config {
+                    access_key: required(take("access_key"), "AWS SigV4", "access_key")?,
+                    secret_key: required(take("secret_key"), "AWS SigV4", "secret_key")?,
+                    session_token: (!session_token.is_empty()).then_some(session_token),
+                    region: required(take("region"), "AWS SigV4", "region")?,
+                    service: required(take("service"), "AWS SigV4", "service")?,
+                })
+            }
+            "hmac" => {
+                let header = take("header");
+                let template = take("template");
+                let timestamp_header = take("timestamp_header");
+                Self::Hmac(HmacConfig {
+                    secret: required(take("secret"), "HMAC", "secret")?,
+                    algorithm: HmacAlgorithm::parse(&take("algorithm"))?,
+                    encoding: SignatureEncoding::parse(&take("encoding"))?,
+                    header: if header.is_empty() {
+                        "X-Signature".to_string()
+                    } else {
+                        header
+                    },
+                    prefix: take("prefix"),
+                    template: parse_template(if template.is_empty() {
+                        DEFAULT_TEMPLATE
+                    } else {
+                        &template
+                    })?,
+                    timestamp_header: (!timestamp_header.is_empty()).then_some(timestamp_header),
+                })
+            }
+            other => return Err(format!("Unknown auth type `{other}`; use {SCHEMES}")),
+        })
+    }
+
+    /// Adds the credentials sent up front. A header typed in the headers
+    /// panel wins over the one the scheme would add.
+    pub fn apply(&self, headers: &mut Vec<(String, String)>, query: &mut Vec<(String, String)>) {
+        let header = match self {
+            Self::Basic { username, password } => Some((
+                "Authorization".to_string(),
+                format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{username}:{password}"))
+                ),
+            )),
+            Self::Bearer { token } => {
+                Some(("Authorization".to_string(), format!("Bearer {token}")))
+            }
+            Self::ApiKey {
+                name,
+                value,
+                location: KeyLocation::Header,
+            } => Some((name.clone(), value.clone())),
+            Self::ApiKey {
+                name,
+                value,
+                location: KeyLocation::Query,
+            } => {
+                query.push((name.clone(), value.clone()));
+                None
+            }
+            Self::Digest { .. }
+            | Self::OAuth2(_)
+            | Self::AwsSigV4(_)
+            | Self::Hmac(_)
+            | Self::None => None,
+        };
+        if let Some((name, value)) = header
+            && !has_header(headers, &name)
+        {
+            headers.push((name, value));
+        }
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().to_ascii_lowercase().replace('-', "_")
+}
+
+fn is_secret(key: &str) -> bool {
+    SECRET_KEYS.contains(&normalize_key(key).as_str())
+}
+
+/// The auth panel text with every secret character replaced by `•`, keeping
+/// `{{variables}}` readable and the length of each line unchanged.
+pub fn mask_for_display(input: &str) -> String {
+    map_secret_values(input, |value| {
+        let mut masked = String::with_capacity(value.len());
+        let mut last = 0;
+        for (range, _) in find_placeholders(value) {
+            masked.extend(value[last..range.start].chars().map(|_| '•'));
+            masked.push_str(&value[range.clone()]);
+            last = range.end;
+        }
+        masked.extend(value[last..].chars().map(|_| '•'));
+        masked
+    })
+}
+
+/// The auth panel text as stored in history: secrets typed in plain text are
+/// replaced, values that only reference variables are kept.
+pub fn redact_secrets(input: &str) -> String {
+    map_secret_values(input, |value| {
+        let mut rest = value.to_string();
+        for (range, _) in find_placeholders(value).into_iter().rev() {
+            rest.replace_range(range, "");
+        }
+        if rest.trim().is_empty() {
+            value.to_string()
+        } else {
+            REDACTED.to_string()
+        }
+    })
+}
+
+fn map_secret_values(input: &str, mut map: impl FnMut(&str) -> String) -> String {
+    input
+        .split('\n')
+        .map(|line| match line.split_once(':') {
+            Some((key, value)) if is_secret(key) => {
+                let trimmed = value.trim_start();
+                let indent = &value[..value.len() - trimmed.len()];
+                format!("{key}:{indent}{}", map(trimmed))
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A `WWW-Authenticate: Digest ...` challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: Option<String>,
+    /// Whether the server offered `qop=auth`; without it the RFC 2069
+    /// response is used.
+    pub qop_auth: bool,
+}
+
+impl DigestChallenge {
+    /// Parses a `WWW-Authenticate` value; `None` when it is not a Digest
+    /// challenge.
+    pub fn parse(header: &str) -> Option<Self> {
+        let (scheme, params) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+        let params = parse_auth_params(params);
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        };
+        Some(Self {
+            realm: param("realm").unwrap_or_default(),
+            nonce: param("nonce")?,
+            opaque: param("opaque"),
+            algorithm: param("algorithm"),
+            qop_auth: param("qop").is_some_and(|qop| {
+                qop.split(',')
+                    .any(|option| option.trim().eq_ignore_ascii_case("auth"))
+            }),
+        })
+    }
+
+    /// The `Authorization` value answering this challenge for a request of
+    /// `method` to `uri` (path and query).
+    pub fn authorization(
+        &self,
+        username: &str,
+        password: &str,
+        method: &str,
+        uri: &str,
+        cnonce: &str,
+    ) -> Result<String, String> {
+        let algorithm = self.algorithm.as_deref().unwrap_or("MD5");
+        let (hash, session): (fn(&str) -> String, bool) =
+            match algorithm.to_ascii_uppercase().as_str() {
+                "MD5" => (md5_hex, false),
+                "MD5-SESS" => (md5_hex, true),
+                "SHA-256" => (sha256_hex, false),
+                "SHA-256-SESS" => (sha256_hex, true),
+                _ => return Err(format!("Unsupported digest algorithm `{algorithm}`")),
+            };
+        let nc = "00000001";
+        let mut ha1 = hash(&format!("{username}:{}:{password}", self.realm));
+        if session {
+            ha1 = hash(&format!("{ha1}:{}:{cnonce}", self.nonce));
+        }
+        let ha2 = hash(&format!("{method}:{uri}"));
+        let response = if self.qop_auth {
+            hash(&format!("{ha1}:{}:{nc}:{cnonce}:auth:{ha2}", self.nonce))
+        } else {
+            hash(&format!("{ha1}:{}:{ha2}", self.nonce))
+        };
+
+        let mut fields = vec![
+            format!("username={}", quote(username)),
+            format!("realm={}", quote(&self.realm)),
+            format!("nonce={}", quote(&self.nonce)),
+            format!("uri={}", quote(uri)),
+        ];
+        if self.algorithm.is_some() {
+            fields.push(format!("algorithm={algorithm}"));
+        }
+        if self.qop_auth {
+            fields.push(format!("qop=auth, nc={nc}, cnonce={}", quote(cnonce)));
+        }
+        fields.push(format!("response={}", quote(&response)));
+        if let Some(opaque) = &self.opaque {
+            fields.push(format!("opaque={}", quote(opaque)));
+        }
+        Ok(format!("Digest {}", fields.join(", ")))
+    }
+}
+
+/// Splits `a=1, b="x, y"` into name/value pairs, unquoting values.
+fn parse_auth_params(input: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ',' || c.is_whitespace()).is_some() {}
+        let name: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        if chars.next().is_none() {
+            break;
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+        } else {
+            value.extend(std::iter::from_fn(|| chars.next_if(|c| *c != ',')));
+        }
+        params.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    params
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn md5_hex(input: &str) -> String {
+    format!("{:x}", Md5::digest(input.as_bytes()))
+}
+
+fn sha256_hex(input: &str) -> String {
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+/// Client nonce for a Digest response.
+pub fn new_cnonce() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    md5_hex(&format!("{nanos}:{}", std::process::id()))[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Auth, DigestChallenge, KeyLocation, mask_for_display, redact_secrets};
+    use crate::types::oauth::{ClientAuth, Grant};
+
+    #[test]
+    fn schemes_add_headers_or_query_params() {
+        let mut headers = Vec::new();
+        let mut query = Vec::new();
+        let basic = Auth::parse("type: basic\nusername: user\npassword: pass").unwrap();
+        basic.apply(&mut headers, &mut query);
+        assert_eq!(
+            headers,
+            [(
+                "Authorization".to_string(),
+                "Basic dXNlcjpwYXNz".to_string()
+            )]
+        );
+
+        let key = Auth::parse("type: api_key\nname: api_key\nvalue: s3cret\nin: query").unwrap();
+        assert!(matches!(
+            key,
+            Auth::ApiKey {
+                location: KeyLocation::Query,
+                ..
+            }
+        ));
+        key.apply(&mut headers, &mut query);
+        assert_eq!(query, [("api_key".to_string(), "s3cret".to_string())]);
+
+        assert_eq!(Auth::parse(""), Ok(Auth::None));
+        assert!(Auth::parse("type: bearer").is_err());
+        assert!(Auth::parse("username: x").is_err());
+
+        let oauth = Auth::parse(
+            "type: oauth2\ngrant: password\ntoken_url: https://auth.test/token\n\
+             client_id: app\nusername: ada\npassword: pw\nclient_auth: body",
+        )
+        .unwrap();
+        let Auth::OAuth2(config) = oauth else {
+            panic!("expected oauth2, got {oauth:?}");
+        };
+        assert_eq!(config.client_auth, ClientAuth::Body);
+        assert!(matches!(config.grant, Grant::Password { .. }));
+        assert!(Auth::parse("type: oauth2\nclient_id: app").is_err());
+        assert!(
+            Auth::parse("type: oauth2\ntoken_url: x\nclient_id: app\ngrant: implicit").is_err()
+        );
+    }
+
+    #[test]
+    fn secrets_are_masked_and_redacted() {
+        let panel = "type: basic\nusername: ada\npassword: hunter2";
+        assert_eq!(
+            mask_for_display(panel),
+            "type: basic\nusername: ada\npassword: •••••••"
+        );
+        assert_eq!(mask_for_display("token: ab{{t}}"), "token: ••{{t}}");
+        assert_eq!(
+            redact_secrets(panel),
+            "type: basic\nusername: ada\npassword: ••••••"
+        );
+        assert_eq!(redact_secrets("token: {{token}}"), "token: {{token}}");
+    }
+
+    #[test]
+    fn digest_response_matches_rfc_2617() {
+        let challenge = DigestChallenge::parse(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .unwrap();
+        let header = challenge
+            .authorization(
+                "Mufasa",
+                "Circle Of Life",
+                "GET",
+                "/dir/index.html",
+                "0a4f113b",
+            )
+            .unwrap();
+        assert!(header.contains("response=\"6629fae49393a05397450978507c4ef1\""));
+        assert!(header.contains("opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""));
+        assert!(DigestChallenge::parse("Basic realm=\"x\"").is_none());
+    }
+}
@@ -0,0 +1,198 @@
+/// Bytes shown in the hex dump; the rest is only reachable by saving.
+const MAX_DUMP_BYTES: usize = 1024 * 1024;
+
+/// Bytes looked at when sniffing a body without a telling Content-Type.
+const SNIFF_BYTES: usize = 8192;
+
+/// Signatures of common binary formats that can start with printable bytes.
+const MAGIC_NUMBERS: &[&[u8]] = &[
+    b"\x89PNG",
+    b"\xff\xd8\xff",
+    b"GIF8",
+    b"%PDF-",
+    b"PK\x03\x04",
+    b"\x1f\x8b",
+    b"BZh",
+    b"\xfd7zXZ",
+    b"7z\xbc\xaf",
+    b"RIFF",
+    b"\x00asm",
+    b"wOFF",
+    b"wOF2",
+];
+
+const TEXT_SUBTYPES: &[&str] = &[
+    "json",
+    "xml",
+    "javascript",
+    "x-javascript",
+    "ecmascript",
+    "yaml",
+    "x-yaml",
+    "x-www-form-urlencoded",
+    "graphql",
+    "sql",
+    "x-sh",
+    "csv",
+];
+
+const BINARY_SUBTYPES: &[&str] = &[
+    "octet-stream",
+    "pdf",
+    "zip",
+    "gzip",
+    "x-gzip",
+    "x-tar",
+    "x-bzip2",
+    "x-xz",
+    "x-7z-compressed",
+    "protobuf",
+    "x-protobuf",
+    "x-google-protobuf",
+    "vnd.google.protobuf",
+    "grpc",
+    "msgpack",
+    "x-msgpack",
+    "cbor",
+    "wasm",
+];
+
+/// Whether a body should be shown as a hex dump rather than text. A
+/// Content-Type that names a text or binary format decides; anything else
+/// is sniffed.
+pub fn is_binary(content_type: Option<&str>, bytes: &[u8]) -> bool {
+    let essence = content_type
+        .and_then(|ct| ct.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let (kind, subtype) = essence.split_once('/').unwrap_or((&essence, ""));
+    if kind == "text"
+        || subtype.ends_with("+json")
+        || subtype.ends_with("+xml")
+        || TEXT_SUBTYPES.contains(&subtype)
+    {
+        return false;
+    }
+    if matches!(kind, "image" | "audio" | "video" | "font") || BINARY_SUBTYPES.contains(&subtype) {
+        return true;
+    }
+    looks_binary(bytes)
+}
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    // UTF-16 text is full of NUL bytes; its byte order mark gives it away.
+    if sample.starts_with(b"\xff\xfe") || sample.starts_with(b"\xfe\xff") {
+        return false;
+    }
+    if MAGIC_NUMBERS.iter().any(|magic| sample.starts_with(magic)) || sample.contains(&0) {
+        return true;
+    }
+    let control = sample
+        .iter()
+        .filter(|&&byte| byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    control * 10 > sample.len()
+}
+
+/// Human-readable size, e.g. `512 B` or `12.3 KiB`.
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// What the response panel shows for a binary body: a size summary and a
+/// `hexdump -C` style dump of the first megabyte.
+pub fn binary_summary(content_type: Option<&str>, bytes: &[u8]) -> String {
+    let kind = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(str::trim)
+        .filter(|ct| !ct.is_empty())
+        .unwrap_or("unknown type");
+    let mut text = format!(
+        "Binary response: {kind}, {} ({} bytes). Press w to save it to a file.\n\n",
+        format_size(bytes.len()),
+        bytes.len()
+    );
+    text.push_str(&hex_dump(&bytes[..bytes.len().min(MAX_DUMP_BYTES)]));
+    if bytes.len() > MAX_DUMP_BYTES {
+        text.push_str(&format!(
+            "\n… {} more not shown",
+            format_size(bytes.len() - MAX_DUMP_BYTES)
+        ));
+    }
+    text
+}
+
+/// Offset, sixteen hex bytes in two groups of eight, and the printable
+/// ASCII, one line per sixteen bytes.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let mut line = format!("{:08x} ", row * 16);
+            for i in 0..16 {
+                if i % 8 == 0 {
+                    line.push(' ');
+                }
+                match chunk.get(i) {
+                    Some(byte) => line.push_str(&format!("{byte:02x} ")),
+                    None => line.push_str("   "),
+                }
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{line} |{ascii}|")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, hex_dump, is_binary};
+
+    #[test]
+    fn detects_binary_by_type_and_content() {
+        assert!(is_binary(Some("image/png"), b""));
+        assert!(is_binary(Some("application/pdf"), b"%PDF-1.7"));
+        assert!(!is_binary(Some("image/svg+xml"), b"<svg/>"));
+        assert!(!is_binary(Some("text/plain"), b"a\0b"));
+        assert!(is_binary(None, b"\x1f\x8b\x08\x00"));
+        assert!(is_binary(Some("application/x-unknown"), b"ab\0cd"));
+        assert!(!is_binary(None, "caf\u{e9} au lait\n".as_bytes()));
+        assert!(!is_binary(None, b"caf\xe9 au lait\n"));
+        assert!(!is_binary(None, b"\xff\xfeh\0i\0"));
+    }
+
+    #[test]
+    fn hex_dump_pads_the_last_line() {
+        let dump = hex_dump(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\x01\x02");
+        assert_eq!(
+            dump,
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n\
+             00000010  01 02                                             |..|"
+        );
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(12_632), "12.3 KiB");
+    }
+}
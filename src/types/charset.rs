@@ -0,0 +1,192 @@
+use encoding_rs::{Encoding, UTF_8};
+use once_cell::sync::Lazy;
+use regex::bytes::Regex;
+
+use crate::types::{
+    app::App,
+    binary::{binary_summary, is_binary},
+    prompt::PromptKind,
+};
+
+/// How far into the body `<meta charset>` and `<?xml encoding?>` are looked
+/// for, as in the HTML prescan.
+const SNIFF_BYTES: usize = 1024;
+
+static META_CHARSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<meta[^>]*?charset\s*=\s*["']?\s*([a-z0-9_.:-]+)"#)
+        .expect("meta charset regex")
+});
+
+static XML_ENCODING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^<\?xml[^>]*?encoding\s*=\s*["']([A-Za-z0-9_.:-]+)"#).expect("xml encoding regex")
+});
+
+/// Where the encoding of a response body came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharsetSource {
+    Manual,
+    Bom,
+    Header,
+    Meta,
+    Default,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedCharset {
+    pub encoding: &'static Encoding,
+    pub source: CharsetSource,
+}
+
+impl DetectedCharset {
+    /// Encoding name with its source, e.g. `Shift_JIS (meta)`.
+    pub fn label(&self) -> String {
+        let name = self.encoding.name();
+        match self.source {
+            CharsetSource::Manual => format!("{name} (manual)"),
+            CharsetSource::Bom => format!("{name} (BOM)"),
+            CharsetSource::Meta => format!("{name} (meta)"),
+            CharsetSource::Header | CharsetSource::Default => name.to_string(),
+        }
+    }
+}
+
+/// Picks the encoding of a body: a manual override, then a byte order mark,
+/// the Content-Type `charset`, an HTML `<meta charset>` or XML declaration,
+/// and finally UTF-8.
+pub fn detect_charset(
+    content_type: Option<&str>,
+    bytes: &[u8],
+    manual: Option<&'static Encoding>,
+) -> DetectedCharset {
+    let (encoding, source) = if let Some(encoding) = manual {
+        (encoding, CharsetSource::Manual)
+    } else if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        (encoding, CharsetSource::Bom)
+    } else if let Some(encoding) = content_type.and_then(header_charset) {
+        (encoding, CharsetSource::Header)
+    } else if let Some(encoding) = sniff_charset(bytes) {
+        (encoding, CharsetSource::Meta)
+    } else {
+        (UTF_8, CharsetSource::Default)
+    };
+    DetectedCharset { encoding, source }
+}
+
+/// Decodes `bytes` to text, replacing malformed sequences.
+pub fn decode_body(
+    content_type: Option<&str>,
+    bytes: &[u8],
+    manual: Option<&'static Encoding>,
+) -> (String, DetectedCharset) {
+    let detected = detect_charset(content_type, bytes, manual);
+    let text = match detected.source {
+        CharsetSource::Manual => detected.encoding.decode_without_bom_handling(bytes).0,
+        _ => detected.encoding.decode_with_bom_removal(bytes).0,
+    };
+    (text.into_owned(), detected)
+}
+
+fn header_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches('"').as_bytes())
+    })
+}
+
+fn sniff_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    let label = XML_ENCODING
+        .captures(head)
+        .or_else(|| META_CHARSET.captures(head))?
+        .get(1)?;
+    Encoding::for_label(label.as_bytes())
+}
+
+impl<'a> App<'a> {
+    /// Turns the received bytes into the text shown in the response panel,
+    /// honoring the charset override.
+    pub(crate) fn decode_response(&mut self) {
+        let state = &mut self.app_state;
+        let Some(bytes) = state.response_bytes.as_deref() else {
+            return;
+        };
+        let content_type = state.response_content_type.as_deref();
+        state.response_binary = state.charset_override.is_none() && is_binary(content_type, bytes);
+        if state.response_binary {
+            state.response_body = Some(binary_summary(content_type, bytes));
+            state.response_charset = None;
+        } else {
+            let (text, detected) = decode_body(content_type, bytes, state.charset_override);
+            state.response_body = Some(text);
+            state.response_charset = Some(detected);
+        }
+    }
+
+    pub(crate) fn prompt_response_charset(&mut self) {
+        let current = self
+            .app_state
+            .charset_override
+            .map(Encoding::name)
+            .unwrap_or_default();
+        self.open_prompt(PromptKind::ResponseCharset, current);
+    }
+
+    /// Forces responses to be decoded as `label`; an empty label goes back
+    /// to detecting the charset.
+    pub(crate) fn set_response_charset(&mut self, label: &str) -> Result<(), String> {
+        let encoding = if label.is_empty() {
+            None
+        } else {
+            Some(
+                Encoding::for_label(label.as_bytes())
+                    .ok_or_else(|| format!("Unknown charset `{label}`"))?,
+            )
+        };
+        self.app_state.charset_override = encoding;
+        self.app_state.status_message = Some(match encoding {
+            Some(encoding) => format!("Decoding responses as {}", encoding.name()),
+            None => "Detecting the response charset automatically".to_string(),
+        });
+        self.decode_response();
+        self.refresh_response_body_cache();
+        self.rebuild_response_tree();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{SHIFT_JIS, UTF_8, WINDOWS_1252};
+
+    use super::{CharsetSource, decode_body};
+
+    #[test]
+    fn decodes_by_bom_header_or_markup_unless_overridden() {
+        let (text, detected) =
+            decode_body(Some("text/plain; charset=ISO-8859-1"), b"caf\xe9", None);
+        assert_eq!(text, "café");
+        assert_eq!(detected.encoding, WINDOWS_1252);
+        assert_eq!(detected.source, CharsetSource::Header);
+
+        let page = b"<html><head><meta charset=\"shift_jis\"></head>\x93\xfa\x96\x7b</html>";
+        let (text, detected) = decode_body(Some("text/html"), page, None);
+        assert!(text.contains("日本"));
+        assert_eq!(detected.label(), "Shift_JIS (meta)");
+
+        let xml = b"<?xml version=\"1.0\" encoding=\"windows-1252\"?><a>\x80</a>";
+        assert!(decode_body(None, xml, None).0.contains('€'));
+
+        let (text, detected) =
+            decode_body(Some("text/plain; charset=latin1"), b"\xef\xbb\xbfok", None);
+        assert_eq!((text.as_str(), detected.encoding), ("ok", UTF_8));
+
+        let (text, detected) = decode_body(None, b"\x93\xfa", Some(SHIFT_JIS));
+        assert_eq!(
+            (text.as_str(), detected.label().as_str()),
+            ("日", "Shift_JIS (manual)")
+        );
+    }
+}
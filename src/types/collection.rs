@@ -0,0 +1,473 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::types::{
+    app::{ActivePanel, App},
+    prompt::PromptKind,
+    request::RequestSpec,
+};
+
+/// Project-local directory holding everything parsel saves for a workspace.
+pub const WORKSPACE_DIR: &str = ".parsel";
+const COLLECTIONS_DIR: &str = "collections";
+const EXTENSION: &str = "toml";
+
+/// A saved request file, addressed by its `folder/name` path relative to the
+/// collections root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionItem {
+    pub folder: String,
+    pub name: String,
+}
+
+impl CollectionItem {
+    pub fn id(&self) -> String {
+        if self.folder.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}/{}", self.folder, self.name)
+        }
+    }
+
+    /// Parses a `folder/sub/name` id typed by the user.
+    pub fn from_id(id: &str) -> Result<Self, String> {
+        let segments = id
+            .trim()
+            .trim_end_matches(&format!(".{EXTENSION}"))
+            .split('/')
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        if segments
+            .iter()
+            .any(|s| s.is_empty() || *s == "." || *s == ".." || s.contains('\\'))
+        {
+            return Err(format!("Invalid request name: {id}"));
+        }
+        let (name, folder) = segments.split_last().expect("split yields a segment");
+        Ok(Self {
+            folder: folder.join("/"),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// One row of the collection tree sidebar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectionRow {
+    Folder { name: String, depth: usize },
+    Request { item: usize, depth: usize },
+}
+
+/// Requests saved as one TOML file each under `.parsel/collections`, grouped
+/// into folders so they can be committed alongside the project.
+#[derive(Debug, Default)]
+pub struct Collections {
+    root: PathBuf,
+    items: Vec<CollectionItem>,
+    rows: Vec<CollectionRow>,
+}
+
+impl Collections {
+    pub fn load() -> Self {
+        let mut collections = Self {
+            root: PathBuf::from(WORKSPACE_DIR).join(COLLECTIONS_DIR),
+            ..Default::default()
+        };
+        collections.refresh();
+        collections
+    }
+
+    pub fn items(&self) -> &[CollectionItem] {
+        &self.items
+    }
+
+    pub fn rows(&self) -> &[CollectionRow] {
+        &self.rows
+    }
+
+    pub fn refresh(&mut self) {
+        let mut items = Vec::new();
+        collect_items(&self.root, "", &mut items);
+        sort_items(&mut items);
+        self.rows = build_rows(&items);
+        self.items = items;
+    }
+
+    pub fn read(&self, item: &CollectionItem) -> Result<RequestSpec, String> {
+        let content = fs::read_to_string(self.path(item)).map_err(|e| e.to_string())?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {e}", item.id()))
+    }
+
+    pub fn write(&mut self, item: &CollectionItem, spec: &RequestSpec) -> Result<(), String> {
+        let path = self.path(item);
+        let content = toml::to_string(spec).map_err(|e| e.to_string())?;
+        write_file(&path, &content).map_err(|e| e.to_string())?;
+        self.refresh();
+        Ok(())
+    }
+
+    pub fn exists(&self, item: &CollectionItem) -> bool {
+        self.path(item).is_file()
+    }
+
+    pub fn rename(&mut self, from: &CollectionItem, to: &CollectionItem) -> Result<(), String> {
+        if self.exists(to) {
+            return Err(format!("{} already exists", to.id()));
+        }
+        let target = self.path(to);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::rename(self.path(from), target).map_err(|e| e.to_string())?;
+        self.remove_empty_folder(from);
+        self.refresh();
+        Ok(())
+    }
+
+    /// Copies `item` next to itself with the first free `name copy N` name.
+    pub fn duplicate(&mut self, item: &CollectionItem) -> Result<CollectionItem, String> {
+        let spec = self.read(item)?;
+        let copy = (1..)
+            .map(|n| CollectionItem {
+                folder: item.folder.clone(),
+                name: if n == 1 {
+                    format!("{} copy", item.name)
+                } else {
+                    format!("{} copy {n}", item.name)
+                },
+            })
+            .find(|candidate| !self.exists(candidate))
+            .expect("an unused copy name exists");
+        self.write(&copy, &spec)?;
+        Ok(copy)
+    }
+
+    pub fn delete(&mut self, item: &CollectionItem) -> Result<(), String> {
+        fs::remove_file(self.path(item)).map_err(|e| e.to_string())?;
+        self.remove_empty_folder(item);
+        self.refresh();
+        Ok(())
+    }
+
+    fn path(&self, item: &CollectionItem) -> PathBuf {
+        let mut path = self.root.clone();
+        path.extend(item.folder.split('/').filter(|s| !s.is_empty()));
+        path.push(format!("{}.{EXTENSION}", item.name));
+        path
+    }
+
+    fn remove_empty_folder(&self, item: &CollectionItem) {
+        let mut dir = self.path(item);
+        while dir.pop() && dir != self.root {
+            if fs::remove_dir(&dir).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    pub(crate) fn toggle_collections(&mut self) {
+        self.app_state.show_collections = !self.app_state.show_collections;
+        if self.app_state.show_collections {
+            self.collections.refresh();
+            self.app_state.active_panel = ActivePanel::Collections;
+        } else if self.app_state.active_panel == ActivePanel::Collections {
+            self.app_state.active_panel = ActivePanel::Url;
+        }
+    }
+
+    pub(crate) fn select_next_collection_row(&mut self) {
+        if self.app_state.collection_selected + 1 < self.collections.rows().len() {
+            self.app_state.collection_selected += 1;
+        }
+    }
+
+    pub(crate) fn select_prev_collection_row(&mut self) {
+        self.app_state.collection_selected = self.app_state.collection_selected.saturating_sub(1);
+    }
+
+    fn selected_collection_item(&self) -> Option<CollectionItem> {
+        match self
+            .collections
+            .rows()
+            .get(self.app_state.collection_selected)?
+        {
+            CollectionRow::Request { item, .. } => self.collections.items().get(*item).cloned(),
+            CollectionRow::Folder { .. } => None,
+        }
+    }
+
+    pub(crate) fn load_selected_collection_item(&mut self) {
+        let Some(item) = self.selected_collection_item() else {
+            return;
+        };
+        match self.collections.read(&item) {
+            Ok(spec) => {
+                self.apply_request_spec(spec);
+                self.app_state.status_message = Some(format!("Loaded {}", item.id()));
+                self.loaded_collection_item = Some(item);
+            }
+            Err(message) => self.app_state.error = Some(message),
+        }
+    }
+
+    /// Prompts for a `folder/name`, prefilled with the currently loaded item.
+    pub(crate) fn prompt_save_request(&mut self) {
+        let initial = self
+            .loaded_collection_item
+            .as_ref()
+            .map(CollectionItem::id)
+            .unwrap_or_default();
+        self.open_prompt(PromptKind::SaveRequest, &initial);
+    }
+
+    /// Overwrites the loaded item, or asks for a name if nothing is loaded.
+    pub(crate) fn save_request(&mut self) {
+        let Some(item) = self.loaded_collection_item.clone() else {
+            self.prompt_save_request();
+            return;
+        };
+        if let Err(message) = self.write_collection_item(&item) {
+            self.app_state.error = Some(message);
+        }
+    }
+
+    pub(crate) fn save_request_as(&mut self, id: &str) -> Result<(), String> {
+        let item = CollectionItem::from_id(id)?;
+        self.write_collection_item(&item)
+    }
+
+    fn write_collection_item(&mut self, item: &CollectionItem) -> Result<(), String> {
+        let spec = self.request_spec();
+        self.collections.write(item, &spec)?;
+        self.app_state.status_message = Some(format!("Saved {}", item.id()));
+        self.select_collection_item(item);
+        self.loaded_collection_item = Some(item.clone());
+        Ok(())
+    }
+
+    pub(crate) fn prompt_rename_collection_item(&mut self) {
+        if let Some(item) = self.selected_collection_item() {
+            let id = item.id();
+            self.open_prompt(PromptKind::RenameRequest(item), &id);
+        }
+    }
+
+    pub(crate) fn rename_collection_item(
+        &mut self,
+        item: &CollectionItem,
+        id: &str,
+    ) -> Result<(), String> {
+        let target = CollectionItem::from_id(id)?;
+        if &target == item {
+            return Ok(());
+        }
+        self.collections.rename(item, &target)?;
+        if self.loaded_collection_item.as_ref() == Some(item) {
+            self.loaded_collection_item = Some(target.clone());
+        }
+        self.app_state.status_message = Some(format!("Renamed to {}", target.id()));
+        self.select_collection_item(&target);
+        Ok(())
+    }
+
+    pub(crate) fn duplicate_collection_item(&mut self) {
+        let Some(item) = self.selected_collection_item() else {
+            return;
+        };
+        match self.collections.duplicate(&item) {
+            Ok(copy) => {
+                self.app_state.status_message = Some(format!("Duplicated as {}", copy.id()));
+                self.select_collection_item(&copy);
+            }
+            Err(message) => self.app_state.error = Some(message),
+        }
+    }
+
+    pub(crate) fn prompt_delete_collection_item(&mut self) {
+        if let Some(item) = self.selected_collection_item() {
+            self.open_prompt(PromptKind::DeleteRequest(item), "");
+        }
+    }
+
+    pub(crate) fn delete_collection_item(&mut self, item: &CollectionItem) -> Result<(), String> {
+        self.collections.delete(item)?;
+        if self.loaded_collection_item.as_ref() == Some(item) {
+            self.loaded_collection_item = None;
+        }
+        let last_row = self.collections.rows().len().saturating_sub(1);
+        self.app_state.collection_selected = self.app_state.collection_selected.min(last_row);
+        self.app_state.status_message = Some(format!("Deleted {}", item.id()));
+        Ok(())
+    }
+
+    fn select_collection_item(&mut self, item: &CollectionItem) {
+        let position = self.collections.rows().iter().position(|row| {
+            matches!(row, CollectionRow::Request { item: idx, .. }
+                if self.collections.items().get(*idx) == Some(item))
+        });
+        if let Some(position) = position {
+            self.app_state.collection_selected = position;
+        }
+    }
+}
+
+fn collect_items(dir: &Path, folder: &str, items: &mut Vec<CollectionItem>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            let child = if folder.is_empty() {
+                file_name.to_string()
+            } else {
+                format!("{folder}/{file_name}")
+            };
+            collect_items(&path, &child, items);
+        } else if let Some(name) = file_name.strip_suffix(&format!(".{EXTENSION}")) {
+            items.push(CollectionItem {
+                folder: folder.to_string(),
+                name: name.to_string(),
+            });
+        }
+    }
+}
+
+/// Orders items by folder, compared segment by segment so each folder's
+/// requests stay together (`api-legacy` sorts after `api/v1`, not between
+/// `api` and `api/v1`), then by name.
+fn sort_items(items: &mut [CollectionItem]) {
+    items.sort_by(|a, b| {
+        a.folder
+            .split('/')
+            .cmp(b.folder.split('/'))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+fn build_rows(items: &[CollectionItem]) -> Vec<CollectionRow> {
+    let mut rows = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        let segments = item
+            .folder
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        let shared = open
+            .iter()
+            .zip(&segments)
+            .take_while(|(a, b)| a == b)
+            .count();
+        open.truncate(shared);
+        for segment in &segments[shared..] {
+            rows.push(CollectionRow::Folder {
+                name: segment.to_string(),
+                depth: open.len(),
+            });
+            open.push(segment);
+        }
+        rows.push(CollectionRow::Request {
+            item: idx,
+            depth: segments.len(),
+        });
+    }
+    rows
+}
+
+pub(crate) fn write_file(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollectionItem, CollectionRow, build_rows, sort_items};
+    use crate::types::{app::BodyMode, request::RequestSpec};
+
+    fn item(id: &str) -> CollectionItem {
+        CollectionItem::from_id(id).unwrap()
+    }
+
+    #[test]
+    fn from_id_splits_folder_and_name() {
+        assert_eq!(
+            item("users/admin/list"),
+            CollectionItem {
+                folder: "users/admin".to_string(),
+                name: "list".to_string(),
+            }
+        );
+        assert!(CollectionItem::from_id("../escape").is_err());
+        assert!(CollectionItem::from_id("users//list").is_err());
+    }
+
+    #[test]
+    fn build_rows_emits_each_folder_once() {
+        let mut items = [
+            item("api-legacy/old"),
+            item("api/v1/list"),
+            item("health"),
+            item("api/v1/create"),
+            item("api/login"),
+        ];
+        sort_items(&mut items);
+        assert_eq!(
+            items.iter().map(CollectionItem::id).collect::<Vec<_>>(),
+            [
+                "health",
+                "api/login",
+                "api/v1/create",
+                "api/v1/list",
+                "api-legacy/old"
+            ]
+        );
+        assert_eq!(
+            build_rows(&items),
+            vec![
+                CollectionRow::Request { item: 0, depth: 0 },
+                CollectionRow::Folder {
+                    name: "api".to_string(),
+                    depth: 0
+                },
+                CollectionRow::Request { item: 1, depth: 1 },
+                CollectionRow::Folder {
+                    name: "v1".to_string(),
+                    depth: 1
+                },
+                CollectionRow::Request { item: 2, depth: 2 },
+                CollectionRow::Request { item: 3, depth: 2 },
+                CollectionRow::Folder {
+                    name: "api-legacy".to_string(),
+                    depth: 0
+                },
+                CollectionRow::Request { item: 4, depth: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn request_spec_round_trips_through_toml() {
+        let spec = RequestSpec {
+            method: "POST".to_string(),
+            url: "https://example.com/users".to_string(),
+            body_mode: BodyMode::Form,
+            query: "page: 2\nlimit: 10".to_string(),
+            headers: String::new(),
+            body: "name: \"parsel\"".to_string(),
+            ..Default::default()
+        };
+        let content = toml::to_string(&spec).unwrap();
+        assert!(!content.contains("headers"));
+        assert_eq!(toml::from_str::<RequestSpec>(&content).unwrap(), spec);
+    }
+}
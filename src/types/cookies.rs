@@ -0,0 +1,569 @@
+use std::{fs, path::PathBuf};
+
+use curl_rest::ResponseHeader;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    app::{App, Overlay},
+    collection::{WORKSPACE_DIR, write_file},
+    history::unix_timestamp,
+    prompt::PromptKind,
+    request::{PreparedRequest, has_header},
+    transfer::Received,
+};
+
+const COOKIES_FILE: &str = "cookies.toml";
+/// Keeps the jar out of version control along with the rest of `.parsel`.
+const GITIGNORE_FILE: &str = ".gitignore";
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// A cookie stored from a `Set-Cookie` response header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Lowercased, without a leading dot.
+    pub domain: String,
+    /// Set without a `Domain` attribute: sent to that exact host only.
+    #[serde(default)]
+    pub host_only: bool,
+    pub path: String,
+    /// Unix time the cookie expires at; none for a session cookie.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<u64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+}
+
+impl Cookie {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, url: &url::Url, now: u64) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+        domain_ok
+            && path_matches(&self.path, url.path())
+            && (!self.secure || is_secure(url))
+            && !self.is_expired(now)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CookieFile {
+    #[serde(default)]
+    cookies: Vec<Cookie>,
+}
+
+/// Cookies received in responses, sent back with every matching request.
+/// Cookies with an expiry are kept in `.parsel/cookies.toml`; session
+/// cookies only last as long as the jar.
+#[derive(Debug, Default, Clone)]
+pub struct CookieJar {
+    path: PathBuf,
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    pub fn load() -> Result<Self, String> {
+        Self::load_from(PathBuf::from(WORKSPACE_DIR).join(COOKIES_FILE))
+    }
+
+    fn load_from(path: PathBuf) -> Result<Self, String> {
+        let cookies = match fs::read_to_string(&path) {
+            Ok(content) => {
+                toml::from_str::<CookieFile>(&content)
+                    .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?
+                    .cookies
+            }
+            Err(_) => Vec::new(),
+        };
+        let cookies = cookies
+            .into_iter()
+            .filter(|cookie| cookie.expires.is_some())
+            .collect();
+        let mut jar = Self { path, cookies };
+        jar.purge(unix_timestamp());
+        Ok(jar)
+    }
+
+    /// Writes the cookies with an expiry back to disk. Nothing is created
+    /// until there is a cookie to keep, and the file is git-ignored when it
+    /// is first written.
+    pub fn save(&self) -> Result<(), String> {
+        let cookies = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.expires.is_some())
+            .cloned()
+            .collect::<Vec<_>>();
+        if cookies.is_empty() && !self.path.exists() {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            let gitignore = dir.join(GITIGNORE_FILE);
+            if !gitignore.exists() {
+                write_file(&gitignore, &format!("{COOKIES_FILE}\n"))
+                    .map_err(|e| format!("Failed to write {}: {e}", gitignore.display()))?;
+            }
+        }
+        let content = toml::to_string(&CookieFile { cookies }).map_err(|e| e.to_string())?;
+        write_file(&self.path, &content)
+            .map_err(|e| format!("Failed to write {}: {e}", self.path.display()))
+    }
+
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    /// Drops expired cookies.
+    pub fn purge(&mut self, now: u64) {
+        self.cookies.retain(|cookie| !cookie.is_expired(now));
+    }
+
+    /// The `Cookie` header for a request to `url`: longer paths first, then
+    /// in the order the cookies were first set.
+    pub fn header_for(&self, url: &str, now: u64) -> Option<String> {
+        let url = url::Url::parse(url).ok()?;
+        let mut matching = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.matches(&url, now))
+            .collect::<Vec<_>>();
+        if matching.is_empty() {
+            return None;
+        }
+        matching.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        Some(
+            matching
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    /// Adds the matching cookies to a request about to be sent. A `Cookie`
+    /// header typed in the headers panel wins.
+    pub fn apply(&self, request: &mut PreparedRequest, now: u64) {
+        if has_header(&request.headers, "cookie") {
+            return;
+        }
+        if let Some(header) = self.header_for(&request.full_url(), now) {
+            request.headers.push(("Cookie".to_string(), header));
+        }
+    }
+
+    /// Stores every `Set-Cookie` of a response to a request for `url`.
+    /// Returns how many cookies were set, replaced or removed.
+    pub fn store_response(&mut self, url: &str, headers: &[ResponseHeader], now: u64) -> usize {
+        let Ok(url) = url::Url::parse(url) else {
+            return 0;
+        };
+        headers
+            .iter()
+            .filter(|header| header.name.eq_ignore_ascii_case("set-cookie"))
+            .filter(|header| self.set_cookie(&url, &header.value, now))
+            .count()
+    }
+
+    /// Stores the cookies set along the way to a response: by each redirect
+    /// for its own URL, then by the response itself.
+    pub fn store_received(&mut self, received: &Received, now: u64) -> usize {
+        received
+            .redirects
+            .iter()
+            .map(|redirect| self.store_response(&redirect.url, &redirect.headers, now))
+            .sum::<usize>()
+            + self.store_response(&received.url, &received.response.headers, now)
+    }
+
+    /// Applies one `Set-Cookie` value (RFC 6265 section 5.2 and 5.3).
+    /// Cookies with a `Domain` the request host is not part of are ignored.
+    fn set_cookie(&mut self, url: &url::Url, header: &str, now: u64) -> bool {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let (pair, attributes) = header.split_once(';').unwrap_or((header, ""));
+        let Some((name, value)) = pair.split_once('=') else {
+            return false;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return false;
+        }
+
+        let mut expires = None;
+        let mut max_age = None;
+        let mut domain = None;
+        let mut path = None;
+        let mut secure = false;
+        let mut http_only = false;
+        for attribute in attributes.split(';') {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "expires" => expires = parse_cookie_date(value).or(expires),
+                "max-age" => max_age = value.parse::<i64>().ok().or(max_age),
+                "domain" if !value.is_empty() => {
+                    domain = Some(value.trim_start_matches('.').to_ascii_lowercase());
+                }
+                "path" if value.starts_with('/') => path = Some(value.to_string()),
+                "secure" => secure = true,
+                "httponly" => http_only = true,
+                _ => {}
+            }
+        }
+
+        let expires = match max_age {
+            Some(seconds) if seconds <= 0 => Some(0),
+            Some(seconds) => Some(now.saturating_add(seconds as u64)),
+            None => expires,
+        };
+        let (domain, host_only) = match domain {
+            Some(domain) if domain_matches(&host, &domain) => (domain, false),
+            Some(_) => return false,
+            None => (host, true),
+        };
+        let cookie = Cookie {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain,
+            host_only,
+            path: path.unwrap_or_else(|| default_path(url.path())),
+            expires,
+            secure,
+            http_only,
+        };
+
+        let existing = self.cookies.iter().position(|stored| {
+            stored.name == cookie.name
+                && stored.domain == cookie.domain
+                && stored.path == cookie.path
+        });
+        match (existing, cookie.is_expired(now)) {
+            (Some(index), true) => {
+                self.cookies.remove(index);
+            }
+            (None, true) => return false,
+            (Some(index), false) => self.cookies[index] = cookie,
+            (None, false) => self.cookies.push(cookie),
+        }
+        true
+    }
+
+    pub fn set_value(&mut self, index: usize, value: &str) {
+        if let Some(cookie) = self.cookies.get_mut(index) {
+            cookie.value = value.to_string();
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Cookie> {
+        (index < self.cookies.len()).then(|| self.cookies.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains. IP addresses only
+/// match themselves.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host.parse::<std::net::IpAddr>().is_err()
+            && !host.starts_with('['))
+}
+
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// The directory of the request path, used when `Set-Cookie` has no `Path`.
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+/// `https`, or plain HTTP to this machine, which browsers also treat as
+/// secure.
+fn is_secure(url: &url::Url) -> bool {
+    url.scheme() == "https" || matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"))
+}
+
+/// Parses an `Expires` date the lenient way RFC 6265 section 5.1.1 does,
+/// accepting `Wed, 21 Oct 2015 07:28:00 GMT` and the older variants.
+fn parse_cookie_date(value: &str) -> Option<u64> {
+    let mut time = None;
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+    for token in value
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != ':')
+        .filter(|token| !token.is_empty())
+    {
+        let digits = token.bytes().take_while(u8::is_ascii_digit).count();
+        if time.is_none() && token.contains(':') {
+            let mut parts = token.split(':').map(|part| part.parse::<u64>().ok());
+            if let (Some(Some(h)), Some(Some(m)), Some(Some(s))) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                time = Some((h, m, s));
+                continue;
+            }
+        }
+        if day.is_none() && (1..=2).contains(&digits) {
+            day = token[..digits].parse::<u32>().ok();
+        } else if month.is_none() && token.len() >= 3 && digits == 0 {
+            let prefix = token[..3].to_ascii_lowercase();
+            month = MONTHS
+                .iter()
+                .position(|name| *name == prefix)
+                .map(|index| index as u32 + 1);
+        } else if year.is_none() && (2..=4).contains(&digits) {
+            year = token[..digits].parse::<i64>().ok().map(|year| match year {
+                70..=99 => year + 1900,
+                0..=69 => year + 2000,
+                _ => year,
+            });
+        }
+    }
+    let ((hour, minute, second), day, month, year) = (time?, day?, month?, year?);
+    if !(1..=31).contains(&day) || year < 1601 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86_400 + (hour * 3600 + minute * 60 + second) as i64;
+    Some(seconds.max(0) as u64)
+}
+
+/// Days since 1970-01-01 for a Gregorian date (Howard Hinnant's
+/// `days_from_civil`).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// `in 3d 4h`, `in 12m`, or `session` for cookies without an expiry.
+pub fn format_expiry(cookie: &Cookie, now: u64) -> String {
+    let Some(expires) = cookie.expires else {
+        return "session".to_string();
+    };
+    let left = expires.saturating_sub(now);
+    match left {
+        0 => "expired".to_string(),
+        1..60 => format!("in {left}s"),
+        60..3600 => format!("in {}m", left / 60),
+        3600..86_400 => format!("in {}h {}m", left / 3600, left % 3600 / 60),
+        _ => format!("in {}d {}h", left / 86_400, left % 86_400 / 3600),
+    }
+}
+
+impl<'a> App<'a> {
+    pub(crate) fn open_cookies(&mut self) {
+        self.cookies.purge(unix_timestamp());
+        self.app_state.overlay = Some(Overlay::Cookies);
+        self.app_state.cookie_selected = 0;
+    }
+
+    pub(crate) fn select_next_cookie(&mut self) {
+        if self.app_state.cookie_selected + 1 < self.cookies.cookies().len() {
+            self.app_state.cookie_selected += 1;
+        }
+    }
+
+    pub(crate) fn select_prev_cookie(&mut self) {
+        self.app_state.cookie_selected = self.app_state.cookie_selected.saturating_sub(1);
+    }
+
+    /// Opens a prompt to change the value of the selected cookie.
+    pub(crate) fn prompt_edit_cookie(&mut self) {
+        let index = self.app_state.cookie_selected;
+        let Some(cookie) = self.cookies.cookies().get(index) else {
+            return;
+        };
+        let kind = PromptKind::EditCookie(index, cookie.name.clone());
+        let value = cookie.value.clone();
+        self.open_prompt(kind, &value);
+    }
+
+    pub(crate) fn edit_cookie(&mut self, index: usize, value: &str) -> Result<(), String> {
+        self.cookies.set_value(index, value);
+        self.cookies.save()
+    }
+
+    pub(crate) fn delete_selected_cookie(&mut self) {
+        let index = self.app_state.cookie_selected;
+        let Some(cookie) = self.cookies.remove(index) else {
+            return;
+        };
+        let count = self.cookies.cookies().len();
+        self.app_state.cookie_selected = index.min(count.saturating_sub(1));
+        match self.cookies.save() {
+            Ok(()) => {
+                self.app_state.status_message = Some(format!(
+                    "Deleted cookie {} for {}",
+                    cookie.name, cookie.domain
+                ));
+            }
+            Err(message) => self.app_state.error = Some(message),
+        }
+    }
+
+    pub(crate) fn prompt_clear_cookies(&mut self) {
+        let count = self.cookies.cookies().len();
+        if count > 0 {
+            self.open_prompt(PromptKind::ClearCookies(count), "");
+        }
+    }
+
+    pub(crate) fn clear_cookies(&mut self) -> Result<(), String> {
+        self.cookies.clear();
+        self.app_state.cookie_selected = 0;
+        self.cookies.save()?;
+        self.app_state.status_message = Some("Deleted all cookies".to_string());
+        Ok(())
+    }
+
+    /// Keeps the cookies set by a response and the redirects before it.
+    pub(crate) fn store_cookies(&mut self, received: &Received) {
+        if self.cookies.store_received(received, unix_timestamp()) == 0 {
+            return;
+        }
+        if let Err(message) = self.cookies.save() {
+            self.app_state.error = Some(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curl_rest::ResponseHeader;
+
+    use super::{COOKIES_FILE, CookieJar, GITIGNORE_FILE, parse_cookie_date};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn set_cookies(values: &[&str]) -> Vec<ResponseHeader> {
+        values
+            .iter()
+            .map(|value| ResponseHeader {
+                name: "Set-Cookie".to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stores_and_matches_by_domain_path_and_security() {
+        let mut jar = CookieJar::default();
+        let headers = set_cookies(&[
+            "sid=abc; Path=/; HttpOnly",
+            "pref=dark; Domain=.example.com; Path=/app; Max-Age=3600",
+            "tok=s3; Secure",
+            "evil=1; Domain=other.com",
+        ]);
+        let stored = jar.store_response("http://api.example.com/login/form", &headers, NOW);
+        assert_eq!(stored, 3);
+        assert_eq!(jar.cookies()[2].path, "/login");
+        assert_eq!(jar.cookies()[1].expires, Some(NOW + 3600));
+
+        assert_eq!(
+            jar.header_for("http://api.example.com/app/x", NOW)
+                .as_deref(),
+            Some("pref=dark; sid=abc")
+        );
+        assert_eq!(
+            jar.header_for("https://api.example.com/login/form", NOW)
+                .as_deref(),
+            Some("tok=s3; sid=abc")
+        );
+        assert_eq!(
+            jar.header_for("http://www.example.com/application", NOW),
+            None
+        );
+        assert_eq!(
+            jar.header_for("http://www.example.com/app", NOW).as_deref(),
+            Some("pref=dark")
+        );
+        assert_eq!(
+            jar.header_for("http://api.example.com/", NOW + 7200)
+                .as_deref(),
+            Some("sid=abc")
+        );
+
+        let removed = jar.store_response(
+            "http://api.example.com/",
+            &set_cookies(&["sid=; Path=/; Expires=Thu, 01 Jan 1970 00:00:00 GMT"]),
+            NOW,
+        );
+        assert_eq!(removed, 1);
+        assert_eq!(jar.header_for("http://api.example.com/", NOW), None);
+    }
+
+    #[test]
+    fn keeps_session_cookies_out_of_the_file() {
+        let dir = std::env::temp_dir().join(format!("parsel-cookies-{}", std::process::id()));
+        let mut jar = CookieJar::load_from(dir.join(COOKIES_FILE)).unwrap();
+        jar.store_response(
+            "http://example.com/",
+            &set_cookies(&["sid=abc", "pref=dark; Max-Age=3600"]),
+            super::unix_timestamp(),
+        );
+        jar.save().unwrap();
+
+        let loaded = CookieJar::load_from(dir.join(COOKIES_FILE)).unwrap();
+        let names = loaded
+            .cookies()
+            .iter()
+            .map(|cookie| cookie.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["pref"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.join(GITIGNORE_FILE)).unwrap(),
+            "cookies.toml\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parses_cookie_dates() {
+        assert_eq!(
+            parse_cookie_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(
+            parse_cookie_date("Wednesday, 21-Oct-15 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(
+            parse_cookie_date("Sun Nov  6 08:49:37 1994"),
+            Some(784_111_777)
+        );
+        assert_eq!(parse_cookie_date("tomorrow"), None);
+    }
+}
@@ -0,0 +1,628 @@
+use std::collections::VecDeque;
+
+use crate::types::{
+    app::{App, BodyMode, RawType},
+    json_body,
+    prompt::PromptKind,
+    request::RequestSpec,
+};
+
+/// A request parsed from a `curl` command line, plus the arguments that have
+/// no equivalent in parsel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CurlImport {
+    pub spec: RequestSpec,
+    pub unmapped: Vec<String>,
+}
+
+/// Flags that only change curl's own output or behaviour parsel already has.
+const IGNORED_FLAGS: &[&str] = &[
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "--compressed",
+    "-g",
+    "--globoff",
+];
+
+/// Options parsel has no equivalent for that take a value. The value is
+/// skipped with them, so it is not mistaken for the URL.
+const VALUE_FLAGS: &[&str] = &[
+    "-o",
+    "--output",
+    "--output-dir",
+    "-w",
+    "--write-out",
+    "-D",
+    "--dump-header",
+    "-c",
+    "--cookie-jar",
+    "-x",
+    "--proxy",
+    "-U",
+    "--proxy-user",
+    "--proxy-header",
+    "--noproxy",
+    "--cacert",
+    "--capath",
+    "-E",
+    "--cert",
+    "--cert-type",
+    "--key",
+    "--key-type",
+    "--pass",
+    "--ciphers",
+    "--resolve",
+    "--connect-to",
+    "--interface",
+    "--unix-socket",
+    "--oauth2-bearer",
+    "-r",
+    "--range",
+    "-T",
+    "--upload-file",
+    "-C",
+    "--continue-at",
+    "-K",
+    "--config",
+    "-z",
+    "--time-cond",
+    "-Y",
+    "--speed-limit",
+    "-y",
+    "--speed-time",
+    "--limit-rate",
+    "--max-filesize",
+    "--retry-max-time",
+    "--keepalive-time",
+    "--trace",
+    "--trace-ascii",
+    "--stderr",
+];
+
+enum DataKind {
+    Raw,
+    UrlEncode,
+}
+
+pub fn parse_curl(command: &str) -> Result<CurlImport, String> {
+    let mut args = VecDeque::from(split_args(command)?);
+    if args.front().is_some_and(|first| first == "curl") {
+        args.pop_front();
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<(DataKind, String)> = Vec::new();
+    let mut form: Vec<(String, String)> = Vec::new();
+    let mut options: Vec<(String, String)> = Vec::new();
+    let mut user: Option<String> = None;
+    let mut digest = false;
+    let mut aws_sigv4: Option<String> = None;
+    let mut force_get = false;
+    let mut unmapped = Vec::new();
+
+    while let Some(mut arg) = args.pop_front() {
+        if let Some((first, rest)) = split_bundle(&arg) {
+            args.push_front(format!("-{rest}"));
+            arg = first.to_string();
+        }
+        let (flag, inline) = split_flag(&arg);
+        let mut value = |name: &str| -> Result<String, String> {
+            match &inline {
+                Some(value) => Ok(value.clone()),
+                None => args
+                    .pop_front()
+                    .ok_or_else(|| format!("Missing value for {name}")),
+            }
+        };
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value(&flag)?.to_ascii_uppercase()),
+            "-H" | "--header" => {
+                let header = value(&flag)?;
+                match header.split_once(':') {
+                    Some((name, value)) if !name.trim().is_empty() => {
+                        headers.push((name.trim().to_string(), value.trim().to_string()))
+                    }
+                    _ => unmapped.push(format!("-H {header}")),
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" | "--json" => {
+                let body = value(&flag)?;
+                if body.starts_with('@') && flag != "--data-raw" {
+                    unmapped.push(format!("{flag} {body}"));
+                } else {
+                    data.push((DataKind::Raw, body));
+                }
+                if flag == "--json" {
+                    headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                    headers.push(("Accept".to_string(), "application/json".to_string()));
+                }
+            }
+            "--data-urlencode" => data.push((DataKind::UrlEncode, value(&flag)?)),
+            "-u" | "--user" => user = Some(value(&flag)?),
+            "--digest" => digest = true,
+            "--basic" => digest = false,
+            "--aws-sigv4" => aws_sigv4 = Some(value(&flag)?),
+            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value(&flag)?)),
+            "-e" | "--referer" => headers.push(("Referer".to_string(), value(&flag)?)),
+            "-b" | "--cookie" => {
+                let cookie = value(&flag)?;
+                if cookie.contains('=') {
+                    headers.push(("Cookie".to_string(), cookie));
+                } else {
+                    unmapped.push(format!("{flag} {cookie}"));
+                }
+            }
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            "-G" | "--get" => force_get = true,
+            "--url" => url = Some(value(&flag)?),
+            "-L" | "--location" => {
+                options.push(("follow_redirects".to_string(), "true".to_string()));
+                if !options.iter().any(|(key, _)| key == "max_redirects") {
+                    // curl's own limit when following.
+                    options.push(("max_redirects".to_string(), "50".to_string()));
+                }
+            }
+            "--max-redirs" => {
+                options.retain(|(key, _)| key != "max_redirects");
+                options.push(("max_redirects".to_string(), value(&flag)?));
+            }
+            "--connect-timeout" => {
+                options.push(("connect_timeout".to_string(), format!("{}s", value(&flag)?)))
+            }
+            "-m" | "--max-time" => {
+                options.push(("timeout".to_string(), format!("{}s", value(&flag)?)))
+            }
+            "--retry" => options.push(("retries".to_string(), value(&flag)?)),
+            "--retry-delay" => {
+                options.push(("retry_backoff".to_string(), format!("{}s", value(&flag)?)))
+            }
+            "-F" | "--form" | "--form-string" => {
+                let field = value(&flag)?;
+                match field.split_once('=') {
+                    Some((name, value)) if flag == "--form-string" && value.starts_with('@') => {
+                        form.push((name.to_string(), format!("\\{value}")))
+                    }
+                    // `<file` sends a file's content as a text field, which parsel cannot express.
+                    Some((name, value)) if !name.is_empty() && !value.starts_with('<') => {
+                        form.push((name.to_string(), value.to_string()))
+                    }
+                    _ => unmapped.push(format!("{flag} {field}")),
+                }
+            }
+            flag if IGNORED_FLAGS.contains(&flag) => {}
+            flag if VALUE_FLAGS.contains(&flag) => {
+                let skipped = value(flag)?;
+                unmapped.push(format!("{flag} {skipped}"));
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => unmapped.push(arg.clone()),
+            _ => {
+                if url.is_none() {
+                    url = Some(arg.clone());
+                } else {
+                    unmapped.push(arg.clone());
+                }
+            }
+        }
+    }
+
+    let url = url.ok_or_else(|| "No URL found in the curl command".to_string())?;
+    let (base_url, mut query) = split_query(&url);
+
+    let content_type = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.to_ascii_lowercase());
+
+    let mut body_mode = BodyMode::default();
+    let mut raw_type = RawType::default();
+    let mut body = String::new();
+    if !form.is_empty() {
+        body = pairs_to_lines(&form);
+        body_mode = BodyMode::Multipart;
+    } else if !data.is_empty() {
+        let joined = join_data(&data);
+        let parsed_json = serde_json::from_str::<serde_json::Value>(&joined).ok();
+        let is_json = content_type
+            .as_deref()
+            .is_some_and(|ct| ct.contains("json"))
+            || parsed_json.is_some_and(|v| v.is_object() || v.is_array());
+        let is_form = content_type
+            .as_deref()
+            .is_none_or(|ct| ct.contains("x-www-form-urlencoded"));
+        if force_get {
+            query.extend(form_pairs(&joined));
+        } else if is_json {
+            match json_lines(&joined) {
+                Some(lines) => {
+                    body = lines;
+                    body_mode = BodyMode::Json;
+                }
+                None => {
+                    body = joined;
+                    body_mode = BodyMode::Raw;
+                }
+            }
+        } else if is_form {
+            body = pairs_to_lines(&form_pairs(&joined));
+            body_mode = BodyMode::Form;
+        } else {
+            let content_type = content_type.as_deref().unwrap_or_default();
+            raw_type = if content_type.contains("xml") {
+                RawType::Xml
+            } else if content_type.starts_with("text/plain") {
+                RawType::Text
+            } else {
+                RawType::Custom
+            };
+            body = joined;
+            body_mode = BodyMode::Raw;
+        }
+    }
+
+    let method = match method {
+        Some(method) => method,
+        None if force_get => "GET".to_string(),
+        None if !data.is_empty() || !form.is_empty() => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+
+    let auth = match user {
+        Some(credentials) => {
+            let (username, password) = credentials
+                .split_once(':')
+                .unwrap_or((credentials.as_str(), ""));
+            match &aws_sigv4 {
+                // `provider1[:provider2[:region[:service]]]`
+                Some(provider) => {
+                    let mut scope = provider.split(':').skip(2);
+                    let region = scope.next().unwrap_or_default();
+                    let service = scope.next().unwrap_or_default();
+                    format!(
+                        "type: aws_sigv4\naccess_key: {username}\nsecret_key: {password}\n\
+                         region: {region}\nservice: {service}"
+                    )
+                }
+                None => {
+                    let scheme = if digest { "digest" } else { "basic" };
+                    format!("type: {scheme}\nusername: {username}\npassword: {password}")
+                }
+            }
+        }
+        None => String::new(),
+    };
+
+    Ok(CurlImport {
+        spec: RequestSpec {
+            method,
+            url: base_url,
+            body_mode,
+            raw_type,
+            options: pairs_to_lines(&options),
+            query: pairs_to_lines(&query),
+            headers: pairs_to_lines(&headers),
+            body,
+            auth,
+        },
+        unmapped,
+    })
+}
+
+/// Splits `--flag=value` into its parts; short flags may carry their value
+/// directly (`-XPOST`).
+fn split_flag(arg: &str) -> (String, Option<String>) {
+    if let Some(long) = arg.strip_prefix("--") {
+        if let Some((name, value)) = long.split_once('=') {
+            return (format!("--{name}"), Some(value.to_string()));
+        }
+        return (arg.to_string(), None);
+    }
+    if arg.len() > 2 && arg.starts_with('-') && arg.is_char_boundary(2) {
+        let (flag, value) = arg.split_at(2);
+        if takes_value(flag) {
+            return (flag.to_string(), Some(value.to_string()));
+        }
+    }
+    (arg.to_string(), None)
+}
+
+/// Splits the first flag off bundled short flags (`-sSL`), unless it takes
+/// the rest of the argument as its value (`-XPOST`, or `-sXPOST` once `-s`
+/// is split off).
+fn split_bundle(arg: &str) -> Option<(&str, &str)> {
+    if arg.len() <= 2 || arg.starts_with("--") || !arg.starts_with('-') || !arg.is_char_boundary(2)
+    {
+        return None;
+    }
+    let (flag, rest) = arg.split_at(2);
+    (!takes_value(flag)).then_some((flag, rest))
+}
+
+fn takes_value(short_flag: &str) -> bool {
+    matches!(
+        short_flag,
+        "-X" | "-H" | "-d" | "-u" | "-A" | "-e" | "-b" | "-F" | "-m"
+    ) || VALUE_FLAGS.contains(&short_flag)
+}
+
+/// Shell-style word splitting covering what "Copy as cURL" produces: single
+/// and double quotes, `$'...'` strings, backslash escapes and line
+/// continuations.
+fn split_args(command: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                Some(escaped) => {
+                    current.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(other) => current.push(other),
+                            None => return Err("Unterminated $'' string".to_string()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("Unterminated $'' string".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => current.push(escaped),
+                            Some('\n') => {}
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            ch if ch.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            ch => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+fn split_query(url: &str) -> (String, Vec<(String, String)>) {
+    let (without_fragment, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = without_fragment.split_once('?') else {
+        return (url.to_string(), Vec::new());
+    };
+    let base = match fragment {
+        Some(fragment) => format!("{base}#{fragment}"),
+        None => base.to_string(),
+    };
+    (base, form_pairs(query))
+}
+
+fn join_data(data: &[(DataKind, String)]) -> String {
+    data.iter()
+        .map(|(kind, value)| match kind {
+            DataKind::Raw => value.clone(),
+            DataKind::UrlEncode => match value.split_once('=') {
+                Some((name, content)) if !name.is_empty() => {
+                    format!("{name}={}", encode_component(content))
+                }
+                Some((_, content)) => encode_component(content),
+                None => encode_component(value),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn encode_component(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+fn form_pairs(input: &str) -> Vec<(String, String)> {
+    url::form_urlencoded::parse(input.as_bytes())
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect()
+}
+
+/// Flattens a JSON object into `key: value` lines for the JSON builder;
+/// `None` when the body is not an object or has keys it cannot express.
+fn json_lines(body: &str) -> Option<String> {
+    let value = serde_json::from_str(body).ok()?;
+    json_body::flatten(&value).map(|pairs| pairs_to_lines(&pairs))
+}
+
+fn pairs_to_lines(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{key}: {value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl<'a> App<'a> {
+    pub(crate) fn prompt_import_curl(&mut self) {
+        self.open_prompt(PromptKind::ImportCurl, "");
+    }
+
+    /// Replaces the request panels with the parsed command and reports any
+    /// arguments that were dropped.
+    pub(crate) fn import_curl(&mut self, command: &str) -> Result<(), String> {
+        let import = parse_curl(command)?;
+        self.apply_request_spec(import.spec);
+        self.loaded_collection_item = None;
+        self.app_state.status_message = Some(if import.unmapped.is_empty() {
+            "Imported cURL command".to_string()
+        } else {
+            format!(
+                "Imported cURL command; could not map: {}",
+                import.unmapped.join(", ")
+            )
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_curl, split_args};
+    use crate::types::app::BodyMode;
+
+    #[test]
+    fn split_args_handles_quotes_and_continuations() {
+        let args =
+            split_args("curl 'https://x.io/a b' \\\n  -H \"X-Name: \\\"q\\\"\" $'a\\nb'").unwrap();
+        assert_eq!(
+            args,
+            vec!["curl", "https://x.io/a b", "-H", "X-Name: \"q\"", "a\nb"]
+        );
+    }
+
+    #[test]
+    fn parses_devtools_style_json_post() {
+        let import = parse_curl(
+            "curl 'https://api.example.com/users?page=2&q=a%20b' \
+             -H 'content-type: application/json' \
+             --data-raw '{\"name\":\"parsel\",\"age\":3}' --compressed -k",
+        )
+        .unwrap();
+        assert_eq!(import.spec.method, "POST");
+        assert_eq!(import.spec.url, "https://api.example.com/users");
+        assert_eq!(import.spec.query, "page: 2\nq: a b");
+        assert_eq!(import.spec.headers, "content-type: application/json");
+        assert_eq!(import.spec.body_mode, BodyMode::Json);
+        assert_eq!(import.spec.body, "name: parsel\nage: 3");
+        assert_eq!(import.unmapped, vec!["-k".to_string()]);
+    }
+
+    #[test]
+    fn parses_form_data_basic_auth_and_get_flag() {
+        let import =
+            parse_curl("curl -XPUT -u user:pass -d a=1 --data-urlencode 'b=x y' https://x.io/form")
+                .unwrap();
+        assert_eq!(import.spec.method, "PUT");
+        assert_eq!(
+            import.spec.auth,
+            "type: basic\nusername: user\npassword: pass"
+        );
+        assert_eq!(import.spec.body_mode, BodyMode::Form);
+        assert_eq!(import.spec.body, "a: 1\nb: x y");
+
+        let import = parse_curl(
+            "curl --aws-sigv4 aws:amz:eu-west-1:s3 -u AKID:secret https://s3.example.com/b",
+        )
+        .unwrap();
+        assert_eq!(
+            import.spec.auth,
+            "type: aws_sigv4\naccess_key: AKID\nsecret_key: secret\nregion: eu-west-1\nservice: s3"
+        );
+
+        let import = parse_curl("curl -G -d q=rust https://x.io/search").unwrap();
+        assert_eq!(import.spec.method, "GET");
+        assert_eq!(import.spec.query, "q: rust");
+        assert!(import.spec.body.is_empty());
+
+        let import =
+            parse_curl("curl -F title=Notes -F 'doc=@/tmp/a.csv;type=text/csv' https://x.io/up")
+                .unwrap();
+        assert_eq!(import.spec.method, "POST");
+        assert_eq!(import.spec.body_mode, BodyMode::Multipart);
+        assert_eq!(
+            import.spec.body,
+            "title: Notes\ndoc: @/tmp/a.csv;type=text/csv"
+        );
+    }
+
+    #[test]
+    fn maps_redirect_and_timeout_flags_to_options() {
+        let import =
+            parse_curl("curl -L --max-redirs 5 -m 30 --connect-timeout 2 https://x.io").unwrap();
+        assert_eq!(
+            import.spec.options,
+            "follow_redirects: true\nmax_redirects: 5\ntimeout: 30s\nconnect_timeout: 2s"
+        );
+    }
+
+    #[test]
+    fn skips_the_values_of_unmapped_flags() {
+        let import =
+            parse_curl("curl -o out.json -w '%{http_code}' --cacert ca.pem https://x.io/a")
+                .unwrap();
+        assert_eq!(import.spec.url, "https://x.io/a");
+        assert_eq!(
+            import.unmapped,
+            vec!["-o out.json", "-w %{http_code}", "--cacert ca.pem"]
+        );
+    }
+
+    #[test]
+    fn expands_bundled_short_flags() {
+        let import = parse_curl("curl -sSL https://x.io/a").unwrap();
+        assert_eq!(import.spec.url, "https://x.io/a");
+        assert_eq!(
+            import.spec.options,
+            "follow_redirects: true\nmax_redirects: 50"
+        );
+        assert!(import.unmapped.is_empty());
+
+        let import = parse_curl("curl -sXPUT -kd a=1 https://x.io/a").unwrap();
+        assert_eq!(import.spec.method, "PUT");
+        assert_eq!(import.spec.body, "a: 1");
+        assert_eq!(import.unmapped, vec!["-k"]);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{collections::BTreeMap, fs, ops::Range, path::PathBuf};
+
+use crate::types::{
+    collection::WORKSPACE_DIR,
+    request::{PreparedRequest, RequestSpec},
+};
+
+const ENVIRONMENTS_FILE: &str = "environments.toml";
+
+pub type Variables = BTreeMap<String, String>;
+
+/// Named variable sets loaded from `.parsel/environments.toml`, one table per
+/// environment:
+///
+/// ```toml
+/// [staging]
+/// host = "https://staging.example.com"
+/// token = "abc123"
+/// ```
+#[derive(Debug, Default)]
+pub struct Environments {
+    envs: BTreeMap<String, Variables>,
+    active: Option<String>,
+    empty: Variables,
+}
+
+impl Environments {
+    pub fn load() -> Result<Self, String> {
+        let path = PathBuf::from(WORKSPACE_DIR).join(ENVIRONMENTS_FILE);
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        Self::parse(&content).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let envs = toml::from_str::<BTreeMap<String, BTreeMap<String, toml::Value>>>(content)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|(name, vars)| {
+                let vars = vars
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            toml::Value::String(value) => value,
+                            other => other.to_string(),
+                        };
+                        (key, value)
+                    })
+                    .collect();
+                (name, vars)
+            })
+            .collect();
+        Ok(Self {
+            envs,
+            ..Default::default()
+        })
+    }
+
+    pub fn active_name(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Variables of the active environment, empty when none is selected.
+    pub fn active_variables(&self) -> &Variables {
+        self.active
+            .as_ref()
+            .and_then(|name| self.envs.get(name))
+            .unwrap_or(&self.empty)
+    }
+
+    pub fn activate(&mut self, name: &str) -> Result<(), String> {
+        if !self.envs.contains_key(name) {
+            return Err(format!("Unknown environment: {name}"));
+        }
+        self.active = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Cycles through "no environment" followed by every environment by name.
+    pub fn next(&mut self) {
+        let names = self.envs.keys().collect::<Vec<_>>();
+        self.active = match &self.active {
+            None => names.first().map(|name| name.to_string()),
+            Some(current) => names
+                .iter()
+                .skip_while(|name| **name != current)
+                .nth(1)
+                .map(|name| name.to_string()),
+        };
+    }
+
+    pub fn prev(&mut self) {
+        let names = self.envs.keys().collect::<Vec<_>>();
+        self.active = match &self.active {
+            None => names.last().map(|name| name.to_string()),
+            Some(current) => names
+                .iter()
+                .rev()
+                .skip_while(|name| **name != current)
+                .nth(1)
+                .map(|name| name.to_string()),
+        };
+    }
+
+    /// Resolves every placeholder in the request, or lists the variables that
+    /// have no value in the active environment.
+    pub fn resolve_spec(&self, spec: &RequestSpec) -> Result<RequestSpec, Vec<String>> {
+        let vars = self.active_variables();
+        let mut unresolved = Vec::new();
+        let mut resolve = |input: &str| {
+            let (value, missing) = substitute(input, vars);
+            for name in missing {
+                if !unresolved.contains(&name) {
+                    unresolved.push(name);
+                }
+            }
+            value
+        };
+        let resolved = RequestSpec {
+            method: spec.method.clone(),
+            url: resolve(&spec.url),
+            body_mode: spec.body_mode,
+            raw_type: spec.raw_type,
+            query: resolve(&spec.query),
+            headers: resolve(&spec.headers),
+            body: resolve(&spec.body),
+            options: resolve(&spec.options),
+            auth: resolve(&spec.auth),
+        };
+        if unresolved.is_empty() {
+            Ok(resolved)
+        } else {
+            Err(unresolved)
+        }
+    }
+
+    /// Resolves the request against the active environment and builds it.
+    pub fn prepare(&self, spec: &RequestSpec) -> Result<PreparedRequest, String> {
+        let spec = self.resolve_spec(spec).map_err(|unresolved| {
+            format!(
+                "Unresolved variables: {}. Define them in the active environment.",
+                unresolved.join(", ")
+            )
+        })?;
+        PreparedRequest::from_spec(&spec)
+    }
+}
+
+/// Byte ranges and names of every `{{name}}` placeholder in `input`.
+pub fn find_placeholders(input: &str) -> Vec<(Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = input[offset..].find("{{") {
+        let start = offset + start;
+        let Some(len) = input[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        let name = input[start + 2..end - 2].trim();
+        if is_variable_name(name) {
+            found.push((start..end, name));
+            offset = end;
+        } else {
+            offset = start + 2;
+        }
+    }
+    found
+}
+
+/// Replaces known placeholders and returns the names of unknown ones, which
+/// are left in place.
+pub fn substitute(input: &str, vars: &Variables) -> (String, Vec<String>) {
+    let mut output = String::with_capacity(input.len());
+    let mut missing = Vec::new();
+    let mut last = 0;
+    for (range, name) in find_placeholders(input) {
+        output.push_str(&input[last..range.start]);
+        match vars.get(name) {
+            Some(value) => output.push_str(value),
+            None => {
+                output.push_str(&input[range.clone()]);
+                missing.push(name.to_string());
+            }
+        }
+        last = range.end;
+    }
+    output.push_str(&input[last..]);
+    (output, missing)
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Environments, Variables, substitute};
+
+    #[test]
+    fn substitute_replaces_known_and_reports_unknown() {
+        let vars = Variables::from([("host".to_string(), "localhost:8080".to_string())]);
+        let (value, missing) = substitute("http://{{host}}/users?t={{ token }}&x={{}}", &vars);
+        assert_eq!(value, "http://localhost:8080/users?t={{ token }}&x={{}}");
+        assert_eq!(missing, vec!["token".to_string()]);
+    }
+
+    #[test]
+    fn cycling_includes_no_environment() {
+        let mut envs = Environments::parse("[local]\nport = 8080\n[prod]\nhost = \"x\"").unwrap();
+        envs.next();
+        assert_eq!(envs.active_name(), Some("local"));
+        assert_eq!(envs.active_variables()["port"], "8080");
+        envs.next();
+        assert_eq!(envs.active_name(), Some("prod"));
+        envs.next();
+        assert_eq!(envs.active_name(), None);
+        envs.prev();
+        assert_eq!(envs.active_name(), Some("prod"));
+    }
+}
@@ -0,0 +1,682 @@
+use crate::{
+    types::{
+        app::{App, Overlay, method_label},
+        auth::Auth,
+        multipart::{self, Part},
+        oauth,
+        request::{BodyPayload, PreparedRequest, has_header},
+        send_options::SendOptions,
+        sigv4::SigV4Config,
+    },
+    ui::clipboard::copy_to_clipboard,
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Curl,
+    Httpie,
+    Rust,
+    Python,
+    JavaScript,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 5] = [
+        Self::Curl,
+        Self::Httpie,
+        Self::Rust,
+        Self::Python,
+        Self::JavaScript,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Curl => "curl",
+            Self::Httpie => "HTTPie",
+            Self::Rust => "Rust (curl-rest)",
+            Self::Python => "Python (requests)",
+            Self::JavaScript => "JavaScript (fetch)",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn render(self, request: &PreparedRequest) -> String {
+        match self {
+            Self::Curl => curl(request),
+            Self::Httpie => httpie(request),
+            Self::Rust => rust(request),
+            Self::Python => python(request),
+            Self::JavaScript => javascript(request),
+        }
+    }
+}
+
+fn curl(request: &PreparedRequest) -> String {
+    let mut parts = vec![format!(
+        "curl -X {} {}",
+        method_label(&request.method),
+        shell_quote(&request.full_url())
+    )];
+    for (name, value) in request.effective_headers() {
+        parts.push(format!("-H {}", shell_quote(&format!("{name}: {value}"))));
+    }
+    if let Some(body) = request.body_text() {
+        parts.push(format!("--data-raw {}", shell_quote(body)));
+    }
+    for part in multipart_parts(request) {
+        parts.push(match part {
+            Part::Text { name, value } => {
+                format!("--form-string {}", shell_quote(&format!("{name}={value}")))
+            }
+            Part::File { name, path, .. } => format!(
+                "-F {}",
+                shell_quote(&format!("{name}=@{}{}", path.display(), type_suffix(part)))
+            ),
+        });
+    }
+    if let Some((username, password)) = digest_credentials(request) {
+        parts.push(format!(
+            "--digest -u {}",
+            shell_quote(&format!("{username}:{password}"))
+        ));
+    }
+    if let Some(config) = sigv4_config(request) {
+        parts.push(format!(
+            "--aws-sigv4 {} -u {}",
+            shell_quote(&format!("aws:amz:{}:{}", config.region, config.service)),
+            shell_quote(&format!("{}:{}", config.access_key, config.secret_key))
+        ));
+        if let Some(token) = &config.session_token {
+            parts.push(format!(
+                "-H {}",
+                shell_quote(&format!("X-Amz-Security-Token: {token}"))
+            ));
+        }
+    }
+    let options = &request.options;
+    if options.follow_redirects && options.max_redirects > 0 {
+        parts.push(format!("-L --max-redirs {}", options.max_redirects));
+    }
+    if let Some(timeout) = options.connect_timeout {
+        parts.push(format!("--connect-timeout {}", timeout.as_secs_f64()));
+    }
+    if let Some(timeout) = options.timeout {
+        parts.push(format!("--max-time {}", timeout.as_secs_f64()));
+    }
+    if options.retries > 0 {
+        // curl's own backoff also starts at a second and doubles.
+        parts.push(format!("--retry {}", options.retries));
+    }
+    // parsel always negotiates and decodes compressed responses (gzip, or
+    // Brotli when it is the preferred Accept-Encoding).
+    parts.push("--compressed".to_string());
+    let command = parts.join(" \\\n  ");
+    match signing_note(request, true) {
+        Some(note) => format!("# {note}\n{command}"),
+        None => command,
+    }
+}
+
+fn httpie(request: &PreparedRequest) -> String {
+    let mut parts = vec!["http".to_string()];
+    if let Some(body) = request.body_text() {
+        parts.push(format!("--raw {}", shell_quote(body)));
+    }
+    if matches!(request.body, Some(BodyPayload::Multipart { .. })) {
+        parts.push("--multipart".to_string());
+    }
+    if let Some((username, password)) = digest_credentials(request) {
+        parts.push(format!(
+            "-A digest -a {}",
+            shell_quote(&format!("{username}:{password}"))
+        ));
+    }
+    let options = &request.options;
+    if options.follow_redirects && options.max_redirects > 0 {
+        parts.push(format!(
+            "--follow --max-redirects {}",
+            options.max_redirects
+        ));
+    }
+    if let Some(timeout) = options.timeout.or(options.connect_timeout) {
+        parts.push(format!("--timeout {}", timeout.as_secs_f64()));
+    }
+    parts.push(method_label(&request.method).to_string());
+    parts.push(shell_quote(&request.url));
+    for (name, value) in &request.query {
+        parts.push(shell_quote(&format!("{name}=={value}")));
+    }
+    for (name, value) in request.effective_headers() {
+        parts.push(shell_quote(&format!("{name}:{value}")));
+    }
+    for part in multipart_parts(request) {
+        parts.push(shell_quote(&match part {
+            Part::Text { name, value } => format!("{name}={value}"),
+            Part::File { name, path, .. } => {
+                format!("{name}@{}{}", path.display(), type_suffix(part))
+            }
+        }));
+    }
+    let command = parts.join(" \\\n  ");
+    match signing_note(request, false) {
+        Some(note) => format!("# {note}\n{command}"),
+        None => command,
+    }
+}
+
+fn rust(request: &PreparedRequest) -> String {
+    let mut lines = Vec::new();
+    if digest_credentials(request).is_some() {
+        lines.push(DIGEST_UNSUPPORTED.to_string());
+    }
+    if let Some(note) = signing_note(request, false) {
+        lines.push(format!("// {note}"));
+    }
+    if let Some(BodyPayload::Multipart { parts, boundary }) = &request.body {
+        lines.push("let mut form = Vec::new();".to_string());
+        for part in parts {
+            let head = match part {
+                Part::Text { name, .. } => {
+                    format!(
+                        "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n"
+                    )
+                }
+                Part::File { name, path, .. } => format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                    multipart::file_name(path),
+                    part_content_type(part),
+                ),
+            };
+            lines.push(format!("form.extend_from_slice({head:?}.as_bytes());"));
+            match part {
+                Part::Text { value, .. } => {
+                    lines.push(format!("form.extend_from_slice({value:?}.as_bytes());"))
+                }
+                Part::File { path, .. } => lines.push(format!(
+                    "form.extend_from_slice(&std::fs::read({:?})?);",
+                    path.display().to_string()
+                )),
+            }
+            lines.push("form.extend_from_slice(b\"\\r\\n\");".to_string());
+        }
+        lines.push(format!(
+            "form.extend_from_slice({:?}.as_bytes());",
+            format!("--{boundary}--\r\n")
+        ));
+        lines.push(String::new());
+    }
+    lines.push("let response = curl_rest::Client::default()".to_string());
+    lines.push(format!(
+        "    .method(curl_rest::Method::{:?})",
+        request.method
+    ));
+    for (name, value) in &request.query {
+        lines.push(format!(
+            "    .query_param_kv({}, {})",
+            rust_string(name),
+            rust_string(value)
+        ));
+    }
+    for (name, value) in &request.headers {
+        lines.push(format!(
+            "    .header(curl_rest::Header::Custom({}.into(), {}.into()))",
+            rust_string(name),
+            rust_string(value)
+        ));
+    }
+    if request.brotli {
+        lines.push("    .brotli(true)".to_string());
+    }
+    let max_redirects = request.options.curl_max_redirects();
+    if max_redirects != SendOptions::default().curl_max_redirects() {
+        lines.push(format!("    .max_redirects({max_redirects})"));
+    }
+    match &request.body {
+        Some(BodyPayload::Json(body)) => {
+            lines.push(format!("    .body_json({})", rust_string(body)))
+        }
+        Some(BodyPayload::Form(body)) | Some(BodyPayload::Raw(body)) => {
+            lines.push(format!("    .body_text({})", rust_string(body)))
+        }
+        Some(BodyPayload::Multipart { boundary, .. }) => {
+            if !has_header(&request.headers, "content-type") {
+                lines.push(format!(
+                    "    .header(curl_rest::Header::ContentType({}.into()))",
+                    rust_string(&format!("multipart/form-data; boundary={boundary}"))
+                ));
+            }
+            lines.push("    .body_bytes(form)".to_string());
+        }
+        None => {}
+    }
+    lines.push(format!("    .send({})?;", rust_string(&request.url)));
+    lines.push(String::new());
+    lines.push("println!(\"{}\", response.status);".to_string());
+    lines.push("println!(\"{}\", String::from_utf8_lossy(&response.body));".to_string());
+    lines.join("\n")
+}
+
+fn python(request: &PreparedRequest) -> String {
+    let mut lines = Vec::new();
+    if let Some(note) = signing_note(request, false) {
+        lines.push(format!("# {note}"));
+    }
+    lines.extend([
+        "import requests".to_string(),
+        String::new(),
+        "response = requests.request(".to_string(),
+        format!("    {},", js_string(method_label(&request.method))),
+        format!("    {},", js_string(&request.url)),
+    ]);
+    if !request.query.is_empty() {
+        lines.push("    params=[".to_string());
+        for (name, value) in &request.query {
+            lines.push(format!(
+                "        ({}, {}),",
+                js_string(name),
+                js_string(value)
+            ));
+        }
+        lines.push("    ],".to_string());
+    }
+    let headers = request.effective_headers();
+    if !headers.is_empty() {
+        lines.push("    headers={".to_string());
+        for (name, value) in &headers {
+            lines.push(format!(
+                "        {}: {},",
+                js_string(name),
+                js_string(value)
+            ));
+        }
+        lines.push("    },".to_string());
+    }
+    if let Some(body) = request.body_text() {
+        lines.push(format!("    data={},", js_string(body)));
+    }
+    if let Some((username, password)) = digest_credentials(request) {
+        lines.push(format!(
+            "    auth=requests.auth.HTTPDigestAuth({}, {}),",
+            js_string(username),
+            js_string(password)
+        ));
+    }
+    let parts = multipart_parts(request);
+    let fields = parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Text { name, value } => Some(format!(
+                "        ({}, {}),",
+                js_string(name),
+                js_string(value)
+            )),
+            Part::File { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    if !fields.is_empty() {
+        lines.push("    data=[".to_string());
+        lines.extend(fields);
+        lines.push("    ],".to_string());
+    }
+    let files = parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::File { name, path, .. } => Some(format!(
+                "        ({}, ({}, open({}, \"rb\"), {})),",
+                js_string(name),
+                js_string(&multipart::file_name(path)),
+                js_string(&path.display().to_string()),
+                js_string(&part_content_type(part)),
+            )),
+            Part::Text { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    if !files.is_empty() {
+        lines.push("    files=[".to_string());
+        lines.extend(files);
+        lines.push("    ],".to_string());
+    }
+    let options = &request.options;
+    match (options.connect_timeout, options.timeout) {
+        (Some(connect), Some(read)) => lines.push(format!(
+            "    timeout=({}, {}),",
+            connect.as_secs_f64(),
+            read.as_secs_f64()
+        )),
+        (Some(timeout), None) | (None, Some(timeout)) => {
+            lines.push(format!("    timeout={},", timeout.as_secs_f64()))
+        }
+        (None, None) => {}
+    }
+    if !options.follow_redirects || options.max_redirects == 0 {
+        lines.push("    allow_redirects=False,".to_string());
+    }
+    lines.push(")".to_string());
+    lines.push(String::new());
+    lines.push("print(response.status_code)".to_string());
+    lines.push("print(response.text)".to_string());
+    lines.join("\n")
+}
+
+fn javascript(request: &PreparedRequest) -> String {
+    let mut lines = Vec::new();
+    if digest_credentials(request).is_some() {
+        lines.push(DIGEST_UNSUPPORTED.to_string());
+    }
+    if let Some(note) = signing_note(request, false) {
+        lines.push(format!("// {note}"));
+    }
+    let parts = multipart_parts(request);
+    if !parts.is_empty() {
+        if parts.iter().any(|part| matches!(part, Part::File { .. })) {
+            lines.push("import { openAsBlob } from \"node:fs\";".to_string());
+            lines.push(String::new());
+        }
+        lines.push("const form = new FormData();".to_string());
+        for part in parts {
+            lines.push(match part {
+                Part::Text { name, value } => {
+                    format!("form.append({}, {});", js_string(name), js_string(value))
+                }
+                Part::File { name, path, .. } => format!(
+                    "form.append({}, await openAsBlob({}, {{ type: {} }}), {});",
+                    js_string(name),
+                    js_string(&path.display().to_string()),
+                    js_string(&part_content_type(part)),
+                    js_string(&multipart::file_name(path)),
+                ),
+            });
+        }
+        lines.push(String::new());
+    }
+    lines.push(format!(
+        "const response = await fetch({}, {{",
+        js_string(&request.full_url())
+    ));
+    lines.push(format!(
+        "  method: {},",
+        js_string(method_label(&request.method))
+    ));
+    let headers = request.effective_headers();
+    if !headers.is_empty() {
+        lines.push("  headers: {".to_string());
+        for (name, value) in &headers {
+            lines.push(format!("    {}: {},", js_string(name), js_string(value)));
+        }
+        lines.push("  },".to_string());
+    }
+    if let Some(body) = request.body_text() {
+        lines.push(format!("  body: {},", js_string(body)));
+    }
+    if !parts.is_empty() {
+        lines.push("  body: form,".to_string());
+    }
+    if !request.options.follow_redirects || request.options.max_redirects == 0 {
+        lines.push("  redirect: \"manual\",".to_string());
+    }
+    if let Some(timeout) = request.options.timeout {
+        lines.push(format!(
+            "  signal: AbortSignal.timeout({}),",
+            timeout.as_millis()
+        ));
+    }
+    lines.push("});".to_string());
+    lines.push(String::new());
+    lines.push("console.log(response.status);".to_string());
+    lines.push("console.log(await response.text());".to_string());
+    lines.join("\n")
+}
+
+/// Sent in place of an OAuth 2.0 access token that has not been fetched yet.
+const TOKEN_PLACEHOLDER: &str = "Bearer <token>";
+
+/// Digest auth needs a challenge round trip that these snippets leave out.
+const DIGEST_UNSUPPORTED: &str =
+    "// Digest auth is not included; answer the server's 401 challenge yourself.";
+
+fn digest_credentials(request: &PreparedRequest) -> Option<(&str, &str)> {
+    match &request.auth {
+        Auth::Digest { username, password } => Some((username, password)),
+        _ => None,
+    }
+}
+
+/// Why the snippet leaves out a request signature, or carries a placeholder
+/// token. Signatures cover the send time, so a pasted one would be stale;
+/// curl computes SigV4 itself.
+fn signing_note(request: &PreparedRequest, curl: bool) -> Option<&'static str> {
+    match &request.auth {
+        Auth::OAuth2(_)
+            if request.headers.iter().any(|(name, value)| {
+                name.eq_ignore_ascii_case("authorization") && value == TOKEN_PLACEHOLDER
+            }) =>
+        {
+            Some("No OAuth 2.0 token is cached; replace <token> with an access token.")
+        }
+        Auth::AwsSigV4(_) if !curl => {
+            Some("AWS SigV4 signing is not included; sign the request with an AWS SDK.")
+        }
+        Auth::Hmac(_) => {
+            Some("The HMAC signature header is not included; compute it when sending.")
+        }
+        _ => None,
+    }
+}
+
+fn sigv4_config(request: &PreparedRequest) -> Option<&SigV4Config> {
+    match &request.auth {
+        Auth::AwsSigV4(config) => Some(config),
+        _ => None,
+    }
+}
+
+fn multipart_parts(request: &PreparedRequest) -> &[Part] {
+    match &request.body {
+        Some(BodyPayload::Multipart { parts, .. }) => parts,
+        _ => &[],
+    }
+}
+
+fn part_content_type(part: &Part) -> String {
+    match part {
+        Part::File {
+            path,
+            content_type: None,
+            ..
+        } => multipart::guess_content_type(path).to_string(),
+        Part::File {
+            content_type: Some(content_type),
+            ..
+        } => content_type.clone(),
+        Part::Text { .. } => String::new(),
+    }
+}
+
+/// The `;type=` suffix curl and HTTPie accept after a file path.
+fn type_suffix(part: &Part) -> String {
+    match part {
+        Part::File {
+            content_type: Some(content_type),
+            ..
+        } => format!(";type={content_type}"),
+        _ => String::new(),
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// A double-quoted literal valid in both JavaScript and Python.
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{value:?}"))
+}
+
+fn rust_string(value: &str) -> String {
+    if !value.contains('"') && !value.contains('\\') {
+        return format!("\"{value}\"");
+    }
+    let mut hashes = String::from("#");
+    while value.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{value}\"{hashes}")
+}
+
+impl<'a> App<'a> {
+    pub(crate) fn open_export(&mut self) {
+        self.app_state.export_boundary = multipart::new_boundary();
+        self.app_state.overlay = Some(Overlay::Export);
+    }
+
+    /// The snippet for the current request, using the boundary picked by
+    /// `open_export`. OAuth 2.0 requests carry the cached access token, or
+    /// a placeholder when there is none yet.
+    pub(crate) fn export_snippet(&self) -> Result<String, String> {
+        let mut request = self.prepared_request()?;
+        if let Some(BodyPayload::Multipart { boundary, .. }) = &mut request.body {
+            boundary.clone_from(&self.app_state.export_boundary);
+        }
+        if let Auth::OAuth2(config) = &request.auth
+            && !has_header(&request.headers, "authorization")
+        {
+            let authorization = match oauth::cached_token(config) {
+                Some(token) => token.authorization(),
+                None => TOKEN_PLACEHOLDER.to_string(),
+            };
+            request
+                .headers
+                .push(("Authorization".to_string(), authorization));
+        }
+        Ok(self.app_state.export_format.render(&request))
+    }
+
+    pub(crate) fn copy_export_snippet(&mut self) {
+        let result = self.export_snippet().and_then(|snippet| {
+            copy_to_clipboard(&snippet).map_err(|e| format!("Failed to copy: {e}"))
+        });
+        match result {
+            Ok(()) => {
+                self.app_state.status_message = Some(format!(
+                    "Copied {} snippet to clipboard",
+                    self.app_state.export_format.label()
+                ));
+                self.close_overlay();
+            }
+            Err(message) => self.app_state.error = Some(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::ExportFormat;
+    use crate::types::{
+        app::{App, BodyMode},
+        request::PreparedRequest,
+        request::RequestSpec,
+    };
+
+    fn form_request() -> PreparedRequest {
+        PreparedRequest::from_spec(&RequestSpec {
+            method: "POST".to_string(),
+            url: "https://example.com/login".to_string(),
+            body_mode: BodyMode::Form,
+            query: "next: /home".to_string(),
+            headers: "Accept-Encoding: br, gzip;q=0.5".to_string(),
+            body: "user: o'neil".to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn curl_includes_form_content_type_and_quotes() {
+        let snippet = ExportFormat::Curl.render(&form_request());
+        assert_eq!(
+            snippet,
+            [
+                "curl -X POST 'https://example.com/login?next=%2Fhome' \\",
+                "  -H 'Accept-Encoding: br, gzip;q=0.5' \\",
+                "  -H 'Content-Type: application/x-www-form-urlencoded' \\",
+                "  --data-raw 'user=o%27neil' \\",
+                "  -L --max-redirs 1 \\",
+                "  --compressed",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn snippets_carry_timeouts_and_redirect_policy() {
+        let request = PreparedRequest::from_spec(&RequestSpec {
+            method: "GET".to_string(),
+            url: "https://example.com/slow".to_string(),
+            options: "timeout: 2.5s\nconnect_timeout: 1s\nfollow_redirects: false".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let curl = ExportFormat::Curl.render(&request);
+        assert!(curl.contains("--connect-timeout 1 \\\n  --max-time 2.5"));
+        assert!(!curl.contains("-L"));
+        let python = ExportFormat::Python.render(&request);
+        assert!(python.contains("timeout=(1, 2.5),"));
+        assert!(python.contains("allow_redirects=False,"));
+        let rust = ExportFormat::Rust.render(&request);
+        assert!(rust.contains(".max_redirects(-1)"));
+    }
+
+    #[test]
+    fn rust_snippet_enables_brotli_when_preferred() {
+        let snippet = ExportFormat::Rust.render(&form_request());
+        assert!(snippet.contains(".method(curl_rest::Method::Post)"));
+        assert!(snippet.contains(".brotli(true)"));
+        assert!(snippet.contains(".query_param_kv(\"next\", \"/home\")"));
+    }
+
+    #[test]
+    fn multipart_boundary_is_kept_while_the_overlay_is_open() {
+        let mut app = App::default();
+        app.apply_request_spec(RequestSpec {
+            method: "POST".to_string(),
+            url: "https://example.com/upload".to_string(),
+            body_mode: BodyMode::Multipart,
+            body: "name: Ada".to_string(),
+            ..Default::default()
+        });
+        app.app_state.export_format = ExportFormat::Rust;
+        app.open_export();
+        let snippet = app.export_snippet().unwrap();
+        assert!(snippet.contains(&app.app_state.export_boundary));
+        thread::sleep(Duration::from_millis(2));
+        assert_eq!(app.export_snippet().unwrap(), snippet);
+    }
+
+    #[test]
+    fn oauth_without_a_cached_token_exports_a_placeholder() {
+        let mut app = App::default();
+        app.apply_request_spec(RequestSpec {
+            method: "GET".to_string(),
+            url: "https://example.com/me".to_string(),
+            auth: "type: oauth2\ntoken_url: https://auth.example.com/export-test\n\
+                   client_id: parsel\nclient_secret: s3cret"
+                .to_string(),
+            ..Default::default()
+        });
+        let snippet = app.export_snippet().unwrap();
+        assert!(snippet.starts_with("# No OAuth 2.0 token is cached"));
+        assert!(snippet.contains("-H 'Authorization: Bearer <token>'"));
+    }
+}
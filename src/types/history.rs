@@ -0,0 +1,142 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::request::RequestSpec;
+
+const HISTORY_FILE: &str = "history.jsonl";
+const MAX_ENTRIES: usize = 500;
+
+/// A single sent request together with the outcome of sending it.
+///
+/// The request inputs are stored exactly as typed into the panels so an entry
+/// can be loaded back without any lossy re-formatting.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub request: RequestSpec,
+    pub status: Option<u16>,
+    pub elapsed_ms: u64,
+    pub response_size: usize,
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_ascii_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+        let status = self.status.map(|s| s.to_string()).unwrap_or_default();
+        [
+            self.request.method.as_str(),
+            self.request.url.as_str(),
+            status.as_str(),
+        ]
+        .iter()
+        .any(|field| field.to_ascii_lowercase().contains(&filter))
+    }
+}
+
+/// Sent requests, newest last, persisted as JSON lines under the XDG data dir.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        let path = data_dir().map(|dir| dir.join(HISTORY_FILE));
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect::<Vec<HistoryEntry>>()
+            })
+            .unwrap_or_default();
+        let skip = entries.len().saturating_sub(MAX_ENTRIES);
+        let history = Self {
+            entries: entries.into_iter().skip(skip).collect(),
+            path,
+        };
+        if skip > 0 {
+            history.compact();
+        }
+        history
+    }
+
+    /// Rewrites the history file so it only holds the retained entries.
+    fn compact(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let content = self
+            .entries
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .map(|line| line + "\n")
+            .collect::<String>();
+        let _ = fs::write(path, content);
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Indices of entries matching `filter`, newest first.
+    pub fn filtered(&self, filter: &str) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, entry)| entry.matches(filter))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    pub fn record(&mut self, entry: HistoryEntry) {
+        if let Some(path) = &self.path {
+            let _ = append_line(path, &entry);
+        }
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+}
+
+fn append_line(path: &PathBuf, entry: &HistoryEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+/// `$XDG_DATA_HOME/parsel`, falling back to `~/.local/share/parsel`.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(base.join("parsel"))
+}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,315 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest as _, Sha256, Sha512};
+
+/// Used when the auth panel sets no `template`.
+pub const DEFAULT_TEMPLATE: &str = "{timestamp}{method}{path}{body}";
+
+/// Fields a template can reference as `{name}`.
+const FIELDS: &str = "timestamp, timestamp_ms, method, host, path, query, body or body_sha256";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HmacAlgorithm {
+    Sha1,
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().replace('-', "").as_str() {
+            "" | "sha256" => Ok(Self::Sha256),
+            "sha1" => Ok(Self::Sha1),
+            "sha512" => Ok(Self::Sha512),
+            other => Err(format!(
+                "Unknown algorithm `{other}`; use sha256, sha512 or sha1"
+            )),
+        }
+    }
+
+    fn mac(self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => mac::<Hmac<Sha1>>(key, message),
+            Self::Sha256 => mac::<Hmac<Sha256>>(key, message),
+            Self::Sha512 => mac::<Hmac<Sha512>>(key, message),
+        }
+    }
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+impl SignatureEncoding {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "" | "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            other => Err(format!("Unknown encoding `{other}`; use hex or base64")),
+        }
+    }
+
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Hex => bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+            Self::Base64 => STANDARD.encode(bytes),
+        }
+    }
+}
+
+/// A piece of the canonical string template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Text(String),
+    Timestamp,
+    TimestampMs,
+    Method,
+    Host,
+    /// Path and query, as in the request line.
+    Path,
+    Query,
+    Body,
+    BodySha256,
+}
+
+/// Splits a template such as `{timestamp}.{body}` into its parts. `\n`,
+/// `\r`, `\t` and `\\` are unescaped, since the auth panel holds one line
+/// per key.
+pub fn parse_template(template: &str) -> Result<Vec<TemplatePart>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some('t') => text.push('\t'),
+                Some(other) => text.push(other),
+                None => text.push('\\'),
+            },
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let part = match name.trim() {
+                    "timestamp" => TemplatePart::Timestamp,
+                    "timestamp_ms" => TemplatePart::TimestampMs,
+                    "method" => TemplatePart::Method,
+                    "host" => TemplatePart::Host,
+                    "path" => TemplatePart::Path,
+                    "query" => TemplatePart::Query,
+                    "body" => TemplatePart::Body,
+                    "body_sha256" => TemplatePart::BodySha256,
+                    other => {
+                        return Err(format!(
+                            "Unknown template field `{{{other}}}`; use {FIELDS}"
+                        ));
+                    }
+                };
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(part);
+            }
+            other => text.push(other),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+    Ok(parts)
+}
+
+/// An HMAC over a canonical string built from the request, sent in a
+/// header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HmacConfig {
+    pub secret: String,
+    pub algorithm: HmacAlgorithm,
+    pub encoding: SignatureEncoding,
+    /// Header that carries the signature.
+    pub header: String,
+    /// Put before the encoded signature, e.g. `sha256=`.
+    pub prefix: String,
+    pub template: Vec<TemplatePart>,
+    /// Header that carries the timestamp signed, if any: in milliseconds
+    /// when the template only uses `{timestamp_ms}`, in seconds otherwise.
+    pub timestamp_header: Option<String>,
+}
+
+impl HmacConfig {
+    /// Adds the signature header (and the timestamp header, when set) for a
+    /// request about to be sent. `url` and `body` must be exactly what goes
+    /// on the wire.
+    pub fn sign(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &mut Vec<(String, String)>,
+        body: &[u8],
+        now: SystemTime,
+    ) -> Result<(), String> {
+        let url = url::Url::parse(url).map_err(|e| format!("Invalid URL: {e}"))?;
+        let elapsed = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let message = self.message(method, &url, body, elapsed.as_millis());
+        let signature = self.algorithm.mac(self.secret.as_bytes(), &message);
+        if let Some(name) = &self.timestamp_header {
+            headers.retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+            let timestamp = if self.template.contains(&TemplatePart::TimestampMs)
+                && !self.template.contains(&TemplatePart::Timestamp)
+            {
+                elapsed.as_millis().to_string()
+            } else {
+                elapsed.as_secs().to_string()
+            };
+            headers.push((name.clone(), timestamp));
+        }
+        headers.push((
+            self.header.clone(),
+            format!("{}{}", self.prefix, self.encoding.encode(&signature)),
+        ));
+        Ok(())
+    }
+
+    /// The canonical string the signature is computed over.
+    fn message(&self, method: &str, url: &url::Url, body: &[u8], millis: u128) -> Vec<u8> {
+        let mut message = Vec::new();
+        for part in &self.template {
+            match part {
+                TemplatePart::Text(text) => message.extend_from_slice(text.as_bytes()),
+                TemplatePart::Timestamp => {
+                    message.extend_from_slice((millis / 1000).to_string().as_bytes())
+                }
+                TemplatePart::TimestampMs => {
+                    message.extend_from_slice(millis.to_string().as_bytes())
+                }
+                TemplatePart::Method => message.extend_from_slice(method.as_bytes()),
+                TemplatePart::Host => {
+                    let host = match (url.host_str(), url.port()) {
+                        (Some(host), Some(port)) => format!("{host}:{port}"),
+                        (host, _) => host.unwrap_or_default().to_string(),
+                    };
+                    message.extend_from_slice(host.as_bytes());
+                }
+                TemplatePart::Path => {
+                    message.extend_from_slice(url.path().as_bytes());
+                    if let Some(query) = url.query() {
+                        message.push(b'?');
+                        message.extend_from_slice(query.as_bytes());
+                    }
+                }
+                TemplatePart::Query => {
+                    message.extend_from_slice(url.query().unwrap_or_default().as_bytes())
+                }
+                TemplatePart::Body => message.extend_from_slice(body),
+                TemplatePart::BodySha256 => {
+                    message.extend_from_slice(format!("{:x}", Sha256::digest(body)).as_bytes())
+                }
+            }
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{HmacAlgorithm, HmacConfig, SignatureEncoding, parse_template};
+
+    fn config(template: &str, algorithm: HmacAlgorithm) -> HmacConfig {
+        HmacConfig {
+            secret: "Jefe".to_string(),
+            algorithm,
+            encoding: SignatureEncoding::Hex,
+            header: "X-Signature".to_string(),
+            prefix: String::new(),
+            template: parse_template(template).unwrap(),
+            timestamp_header: None,
+        }
+    }
+
+    fn signature(config: &HmacConfig, method: &str, url: &str, body: &[u8]) -> String {
+        let mut headers = Vec::new();
+        let now = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        config.sign(method, url, &mut headers, body, now).unwrap();
+        headers.pop().unwrap().1
+    }
+
+    #[test]
+    fn signs_the_rendered_template() {
+        // RFC 4231 and RFC 2202, test case 2.
+        let body = b"what do ya want for nothing?";
+        let sha256 = config("{body}", HmacAlgorithm::Sha256);
+        assert_eq!(
+            signature(&sha256, "POST", "https://x.test/", body),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        let sha1 = config("{body}", HmacAlgorithm::Sha1);
+        assert_eq!(
+            signature(&sha1, "POST", "https://x.test/", body),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        );
+
+        let full = config(
+            "{timestamp}\\n{method}\\n{path}\\n{body}",
+            HmacAlgorithm::Sha256,
+        );
+        let url = url::Url::parse("https://x.test:8443/hooks?b=1&a=2").unwrap();
+        assert_eq!(
+            full.message("PUT", &url, b"{}", 1_700_000_000_123),
+            b"1700000000\nPUT\n/hooks?b=1&a=2\n{}"
+        );
+
+        let mut with_headers = config("{timestamp}.{body}", HmacAlgorithm::Sha256);
+        with_headers.encoding = SignatureEncoding::Base64;
+        with_headers.prefix = "v1=".to_string();
+        with_headers.timestamp_header = Some("X-Timestamp".to_string());
+        let mut headers = Vec::new();
+        with_headers
+            .sign(
+                "POST",
+                "https://x.test/",
+                &mut headers,
+                b"{}",
+                UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            )
+            .unwrap();
+        assert_eq!(
+            headers[0],
+            ("X-Timestamp".to_string(), "1700000000".to_string())
+        );
+        assert!(headers[1].1.starts_with("v1=") && headers[1].1.ends_with('='));
+
+        let mut millis = config("{timestamp_ms}.{body}", HmacAlgorithm::Sha256);
+        millis.timestamp_header = Some("X-Timestamp".to_string());
+        let mut headers = Vec::new();
+        millis
+            .sign(
+                "POST",
+                "https://x.test/",
+                &mut headers,
+                b"{}",
+                UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+            )
+            .unwrap();
+        assert_eq!(
+            headers[0],
+            ("X-Timestamp".to_string(), "1700000000123".to_string())
+        );
+
+        assert!(parse_template("{timestamp}{nonce}").is_err());
+    }
+}
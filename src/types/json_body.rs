@@ -0,0 +1,295 @@
+use serde_json::{Map, Value};
+
+/// Highest array index a key may use. The body is rebuilt on every
+/// keystroke, so `a[4000000000]` must not allocate the gap before it.
+const MAX_INDEX: usize = 10_000;
+
+/// One step of a body key such as `user.tags[0]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// `[]`: appends to the array.
+    Append,
+}
+
+/// Builds a JSON object from `key: value` lines. Keys may use dots and
+/// brackets (`user.address.city`, `tags[0]`, `tags[]`) to build nested
+/// objects and arrays; values are typed with [`infer_value`].
+pub fn build(pairs: Vec<(String, String)>) -> Result<Option<Value>, String> {
+    if pairs.is_empty() {
+        return Ok(None);
+    }
+    let mut root = Value::Object(Map::new());
+    for (key, value) in pairs {
+        let path = parse_key(&key)?;
+        insert(&mut root, &path, infer_value(&value), String::new())
+            .map_err(|at| format!("`{key}` conflicts with the value already set at `{at}`"))?;
+    }
+    Ok(Some(root))
+}
+
+/// Types a value: `true`, `false`, `null`, numbers and inline arrays or
+/// objects become JSON values, anything else a string. Wrapping a value in
+/// double quotes (JSON string syntax) or single quotes (taken literally)
+/// forces a string, e.g. `zip: "01234"` or `flag: 'true'`.
+pub fn infer_value(raw: &str) -> Value {
+    let raw = raw.trim();
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        return match serde_json::from_str::<String>(raw) {
+            Ok(value) => Value::String(value),
+            Err(_) => Value::String(raw[1..raw.len() - 1].to_string()),
+        };
+    }
+    if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        return Value::String(raw[1..raw.len() - 1].to_string());
+    }
+    match raw {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        "null" => return Value::Null,
+        _ => {}
+    }
+    let looks_structured = raw.starts_with('[') || raw.starts_with('{');
+    let looks_numeric = raw.starts_with(|c: char| c == '-' || c.is_ascii_digit());
+    if (looks_structured || looks_numeric)
+        && let Ok(value) = serde_json::from_str::<Value>(raw)
+        && !value.is_string()
+    {
+        return value;
+    }
+    Value::String(raw.to_string())
+}
+
+/// The inverse of [`build`]: flattens an object into `key: value` lines that
+/// rebuild the same value, or `None` when a key cannot be written that way.
+pub fn flatten(value: &Value) -> Option<Vec<(String, String)>> {
+    let Value::Object(map) = value else {
+        return None;
+    };
+    let mut pairs = Vec::new();
+    for (key, value) in map {
+        if key.is_empty() || key.contains(['.', '[', ']', ':', '\n']) || key.trim() != key {
+            return None;
+        }
+        flatten_into(key.clone(), value, &mut pairs);
+    }
+    Some(pairs)
+}
+
+fn flatten_into(path: String, value: &Value, pairs: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            let simple = map
+                .keys()
+                .all(|key| !key.is_empty() && !key.contains(['.', '[', ']', ':', '\n']));
+            if !simple {
+                pairs.push((path, value.to_string()));
+                return;
+            }
+            for (key, value) in map {
+                flatten_into(format!("{path}.{key}"), value, pairs);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (idx, value) in items.iter().enumerate() {
+                flatten_into(format!("{path}[{idx}]"), value, pairs);
+            }
+        }
+        Value::String(text) => {
+            let plain = infer_value(text) == *value && text.trim() == text && !text.contains('\n');
+            let literal = if plain {
+                text.clone()
+            } else {
+                value.to_string()
+            };
+            pairs.push((path, literal));
+        }
+        other => pairs.push((path, other.to_string())),
+    }
+}
+
+fn parse_key(key: &str) -> Result<Vec<Segment>, String> {
+    let invalid = || format!("Invalid body key `{key}`");
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = key.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                } else if !matches!(segments.last(), Some(Segment::Index(_) | Segment::Append)) {
+                    return Err(invalid());
+                }
+                if chars.peek().is_none_or(|next| matches!(next, '.' | '[')) {
+                    return Err(invalid());
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+                if segments.is_empty() {
+                    return Err(invalid());
+                }
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => index.push(c),
+                        None => return Err(invalid()),
+                    }
+                }
+                if index.is_empty() {
+                    segments.push(Segment::Append);
+                } else {
+                    let index: usize = index.parse().map_err(|_| invalid())?;
+                    if index > MAX_INDEX {
+                        return Err(format!(
+                            "Array index {index} in `{key}` is too large (at most {MAX_INDEX})"
+                        ));
+                    }
+                    segments.push(Segment::Index(index));
+                }
+                if chars.peek().is_some_and(|next| !matches!(next, '.' | '[')) {
+                    return Err(invalid());
+                }
+            }
+            ']' => return Err(invalid()),
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(Segment::Key(current));
+    }
+    if segments.is_empty() {
+        return Err(invalid());
+    }
+    Ok(segments)
+}
+
+/// Sets `value` at `path` below `node`, creating objects and arrays along the
+/// way. On a conflict returns the path of the value that is in the way.
+fn insert(node: &mut Value, path: &[Segment], value: Value, at: String) -> Result<(), String> {
+    let Some((segment, rest)) = path.split_first() else {
+        *node = value;
+        return Ok(());
+    };
+    match segment {
+        Segment::Key(key) => {
+            let Value::Object(map) = node else {
+                return Err(at);
+            };
+            let at = if at.is_empty() {
+                key.clone()
+            } else {
+                format!("{at}.{key}")
+            };
+            let child = map
+                .entry(key.clone())
+                .or_insert_with(|| container_for(rest));
+            insert(child, rest, value, at)
+        }
+        Segment::Index(index) => {
+            let Value::Array(items) = node else {
+                return Err(at);
+            };
+            if items.len() <= *index {
+                items.resize(index + 1, Value::Null);
+            }
+            if items[*index].is_null() {
+                items[*index] = container_for(rest);
+            }
+            insert(&mut items[*index], rest, value, format!("{at}[{index}]"))
+        }
+        Segment::Append => {
+            let Value::Array(items) = node else {
+                return Err(at);
+            };
+            items.push(container_for(rest));
+            let index = items.len() - 1;
+            insert(&mut items[index], rest, value, format!("{at}[{index}]"))
+        }
+    }
+}
+
+/// The empty value a path continues into: an object before a key, an array
+/// before an index.
+fn container_for(rest: &[Segment]) -> Value {
+    match rest.first() {
+        Some(Segment::Key(_)) => Value::Object(Map::new()),
+        Some(_) => Value::Array(Vec::new()),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{build, flatten, infer_value};
+
+    fn pairs(lines: &[(&str, &str)]) -> Vec<(String, String)> {
+        lines
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn infers_types_and_honours_quotes() {
+        assert_eq!(infer_value("30"), json!(30));
+        assert_eq!(infer_value("-1.5"), json!(-1.5));
+        assert_eq!(
+            infer_value("123456789012345678901234567890").to_string(),
+            "123456789012345678901234567890"
+        );
+        assert_eq!(infer_value("true"), json!(true));
+        assert_eq!(infer_value("null"), json!(null));
+        assert_eq!(infer_value("[1, \"a\"]"), json!([1, "a"]));
+        assert_eq!(infer_value("01234"), json!("01234"));
+        assert_eq!(infer_value("\"30\""), json!("30"));
+        assert_eq!(infer_value("'true'"), json!("true"));
+        assert_eq!(infer_value("[not json"), json!("[not json"));
+    }
+
+    #[test]
+    fn builds_nested_objects_and_arrays() {
+        let value = build(pairs(&[
+            ("user.name", "Ada"),
+            ("user.address.city", "London"),
+            ("tags[0]", "a"),
+            ("tags[]", "b"),
+            ("items[1].id", "7"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "user": {"name": "Ada", "address": {"city": "London"}},
+                "tags": ["a", "b"],
+                "items": [null, {"id": 7}],
+            })
+        );
+        assert_eq!(flatten(&value).map(build).unwrap().unwrap(), Some(value));
+    }
+
+    #[test]
+    fn reports_conflicting_and_invalid_keys() {
+        assert_eq!(
+            build(pairs(&[("a", "1"), ("a.b", "2")])),
+            Err("`a.b` conflicts with the value already set at `a`".to_string())
+        );
+        assert!(build(pairs(&[("a..b", "1")])).is_err());
+        assert!(build(pairs(&[("a[x]", "1")])).is_err());
+        assert_eq!(
+            build(pairs(&[("a[4000000000]", "1")])),
+            Err(
+                "Array index 4000000000 in `a[4000000000]` is too large (at most 10000)"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use jaq_core::{
+    Compiler, Ctx, RcIter,
+    compile::Undefined,
+    load::{self, Arena, File, Loader},
+};
+use jaq_json::Val;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use tui_input::Input;
+
+use crate::{
+    types::app::{ActivePanel, App},
+    ui::sections::response_body::parse_json_body,
+};
+
+/// Outputs kept from one jq run, so `repeat(.)` and friends cannot fill
+/// the memory.
+const MAX_OUTPUTS: usize = 10_000;
+
+/// How long a jq run may take. jq has no way to interrupt a filter such as
+/// `until(false; .)` that never yields, so its thread is left behind and
+/// the run is reported as timed out. No other jq run starts until that
+/// thread ends, so at most one is ever left spinning.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(2);
+
+const STILL_RUNNING: &str = "a timed-out jq filter is still running; waiting for it to end";
+
+/// State of the filter under the response body. The expression itself
+/// lives in `App::filter_input`.
+#[derive(Debug, Default)]
+pub struct ResponseFilter {
+    pub editing: bool,
+    /// Whether the panel shows the filtered result rather than the full body.
+    pub enabled: bool,
+    /// Pretty-printed result of the last expression that ran successfully.
+    pub output: Option<String>,
+    pub error: Option<String>,
+    /// The jq run in progress, on its own thread.
+    run: Option<FilterRun>,
+    /// A jq run given up on whose thread has not ended yet.
+    abandoned: Option<JoinHandle<()>>,
+    /// Set when the expression or body changed during the run, so it runs
+    /// again once this one is done.
+    stale: bool,
+}
+
+impl ResponseFilter {
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// Drops the run in progress, keeping its thread as abandoned if it
+    /// has not ended.
+    fn abandon_run(&mut self) {
+        if let Some(run) = self.run.take()
+            && !run.thread.is_finished()
+        {
+            self.abandoned = Some(run.thread);
+        }
+    }
+
+    /// Whether a run given up on is still going.
+    fn is_blocked(&mut self) -> bool {
+        if self.abandoned.as_ref().is_some_and(JoinHandle::is_finished) {
+            self.abandoned = None;
+        }
+        self.abandoned.is_some()
+    }
+
+    fn show(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(output) => {
+                self.output = Some(output);
+                self.error = None;
+            }
+            Err(message) => self.error = Some(message),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FilterRun {
+    started: Instant,
+    result: Receiver<Result<String, String>>,
+    thread: JoinHandle<()>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterSyntax {
+    Jq,
+    JsonPath,
+}
+
+impl FilterSyntax {
+    /// JSONPath expressions start at the root, `$`; anything else is jq.
+    pub fn of(expression: &str) -> Self {
+        if expression.trim_start().starts_with('$') {
+            Self::JsonPath
+        } else {
+            Self::Jq
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Jq => "jq",
+            Self::JsonPath => "JSONPath",
+        }
+    }
+}
+
+/// Runs `expression` over `value` and pretty-prints the result: one JSON
+/// document per jq output, or the array of JSONPath matches.
+pub fn apply_filter(value: &Value, expression: &str) -> Result<String, String> {
+    match FilterSyntax::of(expression) {
+        FilterSyntax::Jq => run_jq(value, expression, Instant::now() + FILTER_TIMEOUT),
+        FilterSyntax::JsonPath => run_json_path(value, expression),
+    }
+}
+
+fn run_json_path(value: &Value, expression: &str) -> Result<String, String> {
+    let path = JsonPath::parse(expression.trim()).map_err(|e| e.to_string())?;
+    let nodes = path.query(value).all();
+    serde_json::to_string_pretty(&nodes).map_err(|e| e.to_string())
+}
+
+/// Stops between outputs once `deadline` has passed.
+fn run_jq(value: &Value, expression: &str, deadline: Instant) -> Result<String, String> {
+    let program = File {
+        code: expression,
+        path: (),
+    };
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let modules = loader
+        .load(&arena, program)
+        .map_err(|errors| load_error(errors.into_iter().map(|(_, error)| error)))?;
+    let filter = Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            let first = errors
+                .into_iter()
+                .flat_map(|(_, undefined)| undefined)
+                .next();
+            match first {
+                Some((name, Undefined::Var)) => format!("undefined variable `${name}`"),
+                Some((name, Undefined::Label)) => format!("undefined label `{name}`"),
+                Some((name, Undefined::Filter(arity))) => {
+                    format!("undefined filter `{name}/{arity}`")
+                }
+                Some((name, _)) => format!("undefined `{name}`"),
+                None => "invalid expression".to_string(),
+            }
+        })?;
+
+    let inputs = RcIter::new(core::iter::empty());
+    let mut documents = Vec::new();
+    for output in filter
+        .run((Ctx::new([], &inputs), Val::from(value.clone())))
+        .take(MAX_OUTPUTS)
+    {
+        if Instant::now() > deadline {
+            return Err(timed_out());
+        }
+        let output = Value::from(output.map_err(|e| e.to_string())?);
+        documents.push(serde_json::to_string_pretty(&output).map_err(|e| e.to_string())?);
+    }
+    Ok(documents.join("\n"))
+}
+
+fn timed_out() -> String {
+    format!(
+        "gave up after {}s; the filter may never finish",
+        FILTER_TIMEOUT.as_secs()
+    )
+}
+
+/// The first syntax error, phrased for the one-line panel title.
+fn load_error<'s>(mut errors: impl Iterator<Item = load::Error<&'s str>>) -> String {
+    match errors.next() {
+        Some(load::Error::Io(errors)) => errors
+            .into_iter()
+            .next()
+            .map(|(path, message)| format!("{path}: {message}"))
+            .unwrap_or_default(),
+        Some(load::Error::Lex(errors)) => errors
+            .first()
+            .map(|(expect, _)| format!("expected {}", expect.as_str()))
+            .unwrap_or_default(),
+        Some(load::Error::Parse(errors)) => errors
+            .first()
+            .map(|(expect, found)| match found.trim() {
+                "" => format!("expected {}, found end of input", expect.as_str()),
+                found => format!("expected {}, found `{found}`", expect.as_str()),
+            })
+            .unwrap_or_default(),
+        None => "invalid expression".to_string(),
+    }
+}
+
+impl<'a> App<'a> {
+    pub(crate) fn start_filter(&mut self) {
+        self.app_state.active_panel = ActivePanel::ResBody;
+        self.app_state.tree_view = false;
+        self.app_state.filter.editing = true;
+        self.app_state.filter.enabled = true;
+        self.update_filter();
+    }
+
+    /// Re-runs the expression after an edit and shows the result from the
+    /// top.
+    pub(crate) fn update_filter(&mut self) {
+        self.app_state.response_scroll = 0;
+        self.app_state.response_scroll_x = 0;
+        self.refresh_response_body_cache();
+    }
+
+    /// Recomputes the filtered output for the current response. JSONPath
+    /// runs right away; jq runs on a thread and its result is picked up by
+    /// `poll_filter`. An invalid expression keeps the last good output on
+    /// screen next to the error.
+    pub(crate) fn refresh_filter(&mut self) {
+        let expression = self.filter_input.value();
+        let filter = &mut self.app_state.filter;
+        if expression.trim().is_empty() {
+            filter.output = None;
+            filter.error = None;
+            filter.abandon_run();
+            return;
+        }
+        let Some(body) = self.app_state.response_body.as_deref() else {
+            filter.output = None;
+            filter.error = None;
+            filter.abandon_run();
+            return;
+        };
+        if filter.run.is_some() {
+            filter.stale = true;
+            return;
+        }
+        let content_type = self.app_state.response_content_type.as_deref();
+        let Some(value) = parse_json_body(body, content_type) else {
+            filter.error = Some("filters apply to JSON responses only".to_string());
+            return;
+        };
+        if FilterSyntax::of(expression) == FilterSyntax::JsonPath {
+            let result = apply_filter(&value, expression);
+            filter.show(result);
+            return;
+        }
+        if filter.is_blocked() {
+            filter.error = Some(STILL_RUNNING.to_string());
+            filter.stale = true;
+            return;
+        }
+        let expression = expression.to_string();
+        let (tx, rx) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let _ = tx.send(apply_filter(&value, &expression));
+        });
+        filter.run = Some(FilterRun {
+            started: Instant::now(),
+            result: rx,
+            thread,
+        });
+    }
+
+    /// Shows the result of a finished jq run, or gives up on one that is
+    /// taking too long, then starts the next run if the input changed or
+    /// was waiting for an abandoned run to end.
+    pub(crate) fn poll_filter(&mut self) {
+        let filter = &mut self.app_state.filter;
+        let Some(run) = &filter.run else {
+            if filter.abandoned.is_some()
+                && !filter.is_blocked()
+                && std::mem::take(&mut filter.stale)
+            {
+                self.refresh_filter();
+                self.refresh_response_view();
+            }
+            return;
+        };
+        let result = match run.result.try_recv() {
+            Ok(result) => {
+                filter.run = None;
+                result
+            }
+            Err(mpsc::TryRecvError::Empty) if run.started.elapsed() < FILTER_TIMEOUT => return,
+            Err(_) => {
+                filter.abandon_run();
+                Err(timed_out())
+            }
+        };
+        filter.show(result);
+        if std::mem::take(&mut filter.stale) {
+            self.refresh_filter();
+        }
+        self.refresh_response_view();
+    }
+
+    /// Filtered text to show instead of the formatted body, if any.
+    pub(crate) fn filtered_body(&self) -> Option<&str> {
+        let filter = &self.app_state.filter;
+        if filter.enabled {
+            filter.output.as_deref()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn finish_filter(&mut self) {
+        self.app_state.filter.editing = false;
+        if self.filter_input.value().trim().is_empty() {
+            self.clear_filter();
+        }
+    }
+
+    pub(crate) fn clear_filter(&mut self) {
+        self.filter_input = Input::default();
+        let filter = &mut self.app_state.filter;
+        filter.abandon_run();
+        *filter = ResponseFilter {
+            abandoned: filter.abandoned.take(),
+            ..Default::default()
+        };
+        self.update_filter();
+    }
+
+    /// Switches between the filtered result and the full body.
+    pub(crate) fn toggle_filter(&mut self) {
+        if self.filter_input.value().trim().is_empty() {
+            return;
+        }
+        self.app_state.filter.enabled = !self.app_state.filter.enabled;
+        self.update_filter();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use serde_json::json;
+
+    use super::{apply_filter, run_jq, timed_out};
+
+    #[test]
+    fn jq_and_json_path_select_a_slice() {
+        let value = json!({"users": [{"name": "Ada", "age": 36}, {"name": "Alan", "age": 41}]});
+        assert_eq!(
+            apply_filter(&value, ".users[] | select(.age > 40) | .name"),
+            Ok("\"Alan\"".to_string())
+        );
+        assert_eq!(
+            apply_filter(&value, ".users | map(.age)").unwrap(),
+            "[\n  36,\n  41\n]"
+        );
+        assert_eq!(
+            apply_filter(&value, "$.users[*].name").unwrap(),
+            "[\n  \"Ada\",\n  \"Alan\"\n]"
+        );
+        assert!(apply_filter(&value, ".users[").is_err());
+        assert!(apply_filter(&value, "nope").is_err());
+        assert!(apply_filter(&value, "$.users[").is_err());
+        assert_eq!(
+            run_jq(&value, "repeat(.)", Instant::now()),
+            Err(timed_out())
+        );
+    }
+}
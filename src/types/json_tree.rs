@@ -0,0 +1,351 @@
+use serde_json::Value;
+
+use crate::{
+    types::app::{ActivePanel, App},
+    ui::{clipboard::copy_to_clipboard, sections::response_body::parse_json_body},
+};
+
+/// Depth the tree opens at: the root and its direct children are expanded.
+const INITIAL_DEPTH: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeValue {
+    /// A non-empty object or array and its number of children.
+    Object(usize),
+    Array(usize),
+    /// Anything without children, rendered as compact JSON.
+    Leaf(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    pub depth: usize,
+    /// `"name"` for object members, the index for array items, none for
+    /// the root.
+    pub key: Option<String>,
+    pub value: NodeValue,
+    /// Normalized JSONPath of the node, e.g. `$.users[3]['first name']`.
+    pub path: String,
+    parent: Option<usize>,
+    /// One past the last descendant in `JsonTree::nodes`.
+    end: usize,
+}
+
+impl TreeNode {
+    pub fn is_container(&self) -> bool {
+        !matches!(self.value, NodeValue::Leaf(_))
+    }
+}
+
+/// A visible line of the tree: a node, or the closing bracket of an
+/// expanded container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeRow {
+    Open(usize),
+    Close(usize),
+}
+
+/// Foldable view of a JSON document. Nodes are stored in document order,
+/// so a container's descendants are the nodes up to its `end`.
+#[derive(Debug, Default)]
+pub struct JsonTree {
+    nodes: Vec<TreeNode>,
+    collapsed: Vec<bool>,
+    rows: Vec<TreeRow>,
+    /// Index into `rows`.
+    pub cursor: usize,
+}
+
+impl JsonTree {
+    pub fn new(value: &Value) -> Self {
+        let mut tree = Self::default();
+        tree.push(value, None, None, "$".to_string(), 0);
+        tree.collapsed = vec![false; tree.nodes.len()];
+        tree.collapse_to_depth(INITIAL_DEPTH);
+        tree
+    }
+
+    fn push(
+        &mut self,
+        value: &Value,
+        key: Option<String>,
+        parent: Option<usize>,
+        path: String,
+        depth: usize,
+    ) {
+        let index = self.nodes.len();
+        let node_value = match value {
+            Value::Object(map) if !map.is_empty() => NodeValue::Object(map.len()),
+            Value::Array(items) if !items.is_empty() => NodeValue::Array(items.len()),
+            other => NodeValue::Leaf(other.to_string()),
+        };
+        self.nodes.push(TreeNode {
+            depth,
+            key,
+            value: node_value,
+            path: path.clone(),
+            parent,
+            end: index + 1,
+        });
+        match value {
+            Value::Object(map) => {
+                for (name, child) in map {
+                    let key = Value::String(name.clone()).to_string();
+                    self.push(
+                        child,
+                        Some(key),
+                        Some(index),
+                        member_path(&path, name),
+                        depth + 1,
+                    );
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    let path = format!("{path}[{i}]");
+                    self.push(child, Some(i.to_string()), Some(index), path, depth + 1);
+                }
+            }
+            _ => {}
+        }
+        self.nodes[index].end = self.nodes.len();
+    }
+
+    pub fn node(&self, index: usize) -> &TreeNode {
+        &self.nodes[index]
+    }
+
+    pub fn is_collapsed(&self, index: usize) -> bool {
+        self.collapsed[index]
+    }
+
+    pub fn rows(&self) -> &[TreeRow] {
+        &self.rows
+    }
+
+    /// Node under the cursor; a closing bracket belongs to its container.
+    pub fn cursor_node(&self) -> Option<usize> {
+        self.rows.get(self.cursor).map(|row| match row {
+            TreeRow::Open(index) | TreeRow::Close(index) => *index,
+        })
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// Folds or unfolds the container under the cursor.
+    pub fn toggle(&mut self) {
+        if let Some(index) = self.cursor_node()
+            && self.nodes[index].is_container()
+        {
+            self.collapsed[index] = !self.collapsed[index];
+            self.rebuild(index);
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(index) = self.cursor_node()
+            && self.nodes[index].is_container()
+            && self.collapsed[index]
+        {
+            self.collapsed[index] = false;
+            self.rebuild(index);
+        }
+    }
+
+    /// Folds the container under the cursor, or moves to the parent when
+    /// there is nothing left to fold.
+    pub fn collapse(&mut self) {
+        let Some(index) = self.cursor_node() else {
+            return;
+        };
+        if self.nodes[index].is_container() && !self.collapsed[index] {
+            self.collapsed[index] = true;
+            self.rebuild(index);
+        } else if let Some(parent) = self.nodes[index].parent {
+            self.collapsed[parent] = true;
+            self.rebuild(parent);
+        }
+    }
+
+    pub fn expand_all(&mut self) {
+        let current = self.cursor_node();
+        self.collapsed.fill(false);
+        self.rebuild(current.unwrap_or(0));
+    }
+
+    /// Expands everything above `depth` and folds the rest; 0 folds the
+    /// whole document into one line.
+    pub fn collapse_to_depth(&mut self, depth: usize) {
+        let current = self.cursor_node();
+        for (collapsed, node) in self.collapsed.iter_mut().zip(&self.nodes) {
+            *collapsed = node.depth >= depth;
+        }
+        // Keep the cursor on the nearest ancestor that is still visible.
+        let mut target = current.unwrap_or(0);
+        while self.nodes[target].depth > depth {
+            target = self.nodes[target].parent.unwrap_or(0);
+        }
+        self.rebuild(target);
+    }
+
+    /// Recomputes the visible rows and puts the cursor on `target`.
+    fn rebuild(&mut self, target: usize) {
+        self.rows.clear();
+        let mut open: Vec<usize> = Vec::new();
+        let mut index = 0;
+        while index < self.nodes.len() {
+            while let Some(&parent) = open.last() {
+                if index < self.nodes[parent].end {
+                    break;
+                }
+                self.rows.push(TreeRow::Close(parent));
+                open.pop();
+            }
+            self.rows.push(TreeRow::Open(index));
+            let node = &self.nodes[index];
+            if node.is_container() && self.collapsed[index] {
+                index = node.end;
+            } else {
+                if node.is_container() {
+                    open.push(index);
+                }
+                index += 1;
+            }
+        }
+        while let Some(parent) = open.pop() {
+            self.rows.push(TreeRow::Close(parent));
+        }
+        self.cursor = self
+            .rows
+            .iter()
+            .position(|row| *row == TreeRow::Open(target))
+            .unwrap_or(0);
+    }
+}
+
+/// `$.name` for plain identifiers, `$['odd key']` otherwise.
+fn member_path(parent: &str, name: &str) -> String {
+    let mut chars = name.chars();
+    let plain = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        format!("{parent}.{name}")
+    } else {
+        let escaped = name.replace('\\', "\\\\").replace('\'', "\\'");
+        format!("{parent}['{escaped}']")
+    }
+}
+
+impl<'a> App<'a> {
+    /// Switches the response panel between the text and the tree view.
+    pub(crate) fn toggle_response_tree(&mut self) {
+        self.app_state.active_panel = ActivePanel::ResBody;
+        self.app_state.tree_view = !self.app_state.tree_view;
+        self.rebuild_response_tree();
+        if self.app_state.tree_view && self.app_state.response_tree.is_none() {
+            self.app_state.tree_view = false;
+            self.app_state.error = Some("The tree view needs a JSON response".to_string());
+        }
+        self.app_state.response_scroll = 0;
+        self.app_state.response_scroll_x = 0;
+    }
+
+    /// Builds the tree for the current response while the tree view is on.
+    pub(crate) fn rebuild_response_tree(&mut self) {
+        self.app_state.response_tree = None;
+        if !self.app_state.tree_view {
+            return;
+        }
+        let Some(body) = self.app_state.response_body.as_deref() else {
+            return;
+        };
+        let content_type = self.app_state.response_content_type.as_deref();
+        self.app_state.response_tree =
+            parse_json_body(body, content_type).map(|value| JsonTree::new(&value));
+    }
+
+    /// The tree, when the response panel shows it.
+    pub(crate) fn active_response_tree(&self) -> Option<&JsonTree> {
+        if self.app_state.tree_view {
+            self.app_state.response_tree.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Applies `change` to the tree and scrolls the cursor into view.
+    pub(crate) fn update_response_tree(&mut self, change: impl FnOnce(&mut JsonTree)) {
+        if !self.app_state.tree_view {
+            return;
+        }
+        let Some(tree) = self.app_state.response_tree.as_mut() else {
+            return;
+        };
+        change(tree);
+        let cursor = tree.cursor;
+        let height = self.app_state.response_viewport_height.max(1) as usize;
+        let top = self.app_state.response_scroll as usize;
+        if cursor < top {
+            self.app_state.response_scroll = cursor.min(u16::MAX as usize) as u16;
+        } else if cursor >= top + height {
+            self.app_state.response_scroll = (cursor + 1 - height).min(u16::MAX as usize) as u16;
+        }
+    }
+
+    pub(crate) fn copy_tree_path(&mut self) {
+        let Some(path) = self.active_response_tree().and_then(|tree| {
+            tree.cursor_node()
+                .map(|index| tree.node(index).path.clone())
+        }) else {
+            return;
+        };
+        match copy_to_clipboard(&path) {
+            Ok(()) => self.app_state.status_message = Some(format!("Copied {path}")),
+            Err(e) => self.app_state.error = Some(format!("Failed to copy: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{JsonTree, TreeRow};
+
+    #[test]
+    fn folding_tracks_rows_and_paths() {
+        let value = json!({"users": [{"id": 1, "first name": "Ada"}], "count": 1});
+        let mut tree = JsonTree::new(&value);
+        // Root and `users` are open; the user object starts folded.
+        assert_eq!(
+            tree.rows(),
+            [
+                TreeRow::Open(0),
+                TreeRow::Open(1),
+                TreeRow::Open(2),
+                TreeRow::Close(1),
+                TreeRow::Open(5),
+                TreeRow::Close(0),
+            ]
+        );
+
+        tree.move_cursor(2);
+        tree.expand();
+        assert_eq!(tree.rows().len(), 9);
+        tree.move_cursor(2);
+        let index = tree.cursor_node().unwrap();
+        assert_eq!(tree.node(index).path, "$.users[0]['first name']");
+
+        tree.collapse();
+        assert_eq!(tree.node(tree.cursor_node().unwrap()).path, "$.users[0]");
+        tree.collapse_to_depth(0);
+        assert_eq!(tree.rows(), [TreeRow::Open(0)]);
+        tree.expand_all();
+        assert_eq!(tree.rows().len(), 9);
+    }
+}
@@ -1,2 +1,25 @@
 pub mod app;
+pub mod auth;
+pub mod binary;
+pub mod charset;
+pub mod collection;
+pub mod cookies;
+pub mod curl_import;
+pub mod environment;
+pub mod export;
+pub mod history;
+pub mod hmac_signer;
 pub mod input_handler;
+pub mod json_body;
+pub mod json_filter;
+pub mod json_tree;
+pub mod multipart;
+pub mod oauth;
+pub mod prompt;
+pub mod request;
+pub mod response_file;
+pub mod search;
+pub mod send_options;
+pub mod sigv4;
+pub mod transfer;
+pub mod yank;
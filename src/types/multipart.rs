@@ -0,0 +1,223 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// One line of a multipart body: `name: value` or `name: @/path;type=mime`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    Text {
+        name: String,
+        value: String,
+    },
+    File {
+        name: String,
+        path: PathBuf,
+        content_type: Option<String>,
+    },
+}
+
+/// Parses a `key: value` pair. A value starting with `@` is a file path with
+/// an optional `;type=` override; `\@` keeps a literal leading `@`.
+pub fn parse_part(name: &str, value: &str) -> Part {
+    if let Some(literal) = value.strip_prefix("\\@") {
+        return Part::Text {
+            name: name.to_string(),
+            value: format!("@{literal}"),
+        };
+    }
+    let Some(file) = value.strip_prefix('@') else {
+        return Part::Text {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+    };
+    let (path, content_type) = match file.rsplit_once(";type=") {
+        Some((path, content_type)) if !content_type.trim().is_empty() => {
+            (path, Some(content_type.trim().to_string()))
+        }
+        _ => (file, None),
+    };
+    Part::File {
+        name: name.to_string(),
+        path: expand_home(path.trim()),
+        content_type,
+    }
+}
+
+pub fn parse_parts(pairs: Vec<(String, String)>) -> Vec<Part> {
+    pairs
+        .into_iter()
+        .map(|(name, value)| parse_part(&name, &value))
+        .collect()
+}
+
+/// Size of the file behind a file part, or why it cannot be uploaded.
+pub fn check_file(path: &Path) -> Result<u64, String> {
+    match fs::metadata(path) {
+        Ok(meta) if meta.is_file() => Ok(meta.len()),
+        Ok(_) => Err(format!("{} is not a file", path.display())),
+        Err(_) => Err(format!("{} not found", path.display())),
+    }
+}
+
+/// Checks every file part, returning the first problem.
+pub fn validate(parts: &[Part]) -> Result<(), String> {
+    for part in parts {
+        if let Part::File { path, .. } = part {
+            check_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn new_boundary() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("----parsel{:x}{:x}", nanos, std::process::id())
+}
+
+/// Reads the files and encodes every part as `multipart/form-data`.
+pub fn encode(parts: &[Part], boundary: &str) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    for part in parts {
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        match part {
+            Part::Text { name, value } => {
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                        escape_quoted(name)
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(value.as_bytes());
+            }
+            Part::File {
+                name,
+                path,
+                content_type,
+            } => {
+                let contents = fs::read(path)
+                    .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+                let content_type = content_type
+                    .clone()
+                    .unwrap_or_else(|| guess_content_type(path).to_string());
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {content_type}\r\n\r\n",
+                        escape_quoted(name),
+                        escape_quoted(&file_name(path)),
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(&contents);
+            }
+        }
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    Ok(body)
+}
+
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Content type from the file extension, as curl would pick it.
+pub fn guess_content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" => "application/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\r', '\n'], " ")
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Ok(home) = std::env::var("HOME")
+    {
+        return Path::new(&home).join(rest);
+    }
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Part, encode, parse_part};
+
+    #[test]
+    fn parses_file_parts_with_type_override() {
+        assert_eq!(
+            parse_part("avatar", "@/tmp/me.png;type=image/webp"),
+            Part::File {
+                name: "avatar".to_string(),
+                path: PathBuf::from("/tmp/me.png"),
+                content_type: Some("image/webp".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_part("handle", "\\@parsel"),
+            Part::Text {
+                name: "handle".to_string(),
+                value: "@parsel".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn encodes_text_and_file_parts() {
+        let path =
+            std::env::temp_dir().join(format!("parsel-multipart-{}.txt", std::process::id()));
+        std::fs::write(&path, "hello").unwrap();
+        let parts = vec![
+            parse_part("title", "Notes"),
+            parse_part("doc", &format!("@{}", path.display())),
+        ];
+        let body = String::from_utf8(encode(&parts, "XYZ").unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        assert_eq!(
+            body,
+            format!(
+                "--XYZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nNotes\r\n\
+                 --XYZ\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"{file_name}\"\r\n\
+                 Content-Type: text/plain\r\n\r\nhello\r\n--XYZ--\r\n"
+            )
+        );
+    }
+}
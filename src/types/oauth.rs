@@ -0,0 +1,442 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, atomic::AtomicBool},
+    time::{Duration, SystemTime},
+};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use curl_rest::Method;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::types::{
+    auth::Auth,
+    request::{BodyPayload, PreparedRequest, SendError},
+    send_options::SendOptions,
+};
+
+/// A token this close to its expiry is refreshed before use, so it does not
+/// run out in flight.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Access tokens by `OAuthConfig::cache_key`, shared by every request sent
+/// from this process.
+static TOKENS: Lazy<Mutex<HashMap<String, Token>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Grant {
+    ClientCredentials,
+    /// Resource owner password credentials.
+    Password {
+        username: String,
+        password: String,
+    },
+    RefreshToken {
+        refresh_token: String,
+    },
+}
+
+impl Grant {
+    fn grant_type(&self) -> &'static str {
+        match self {
+            Self::ClientCredentials => "client_credentials",
+            Self::Password { .. } => "password",
+            Self::RefreshToken { .. } => "refresh_token",
+        }
+    }
+}
+
+/// How the client authenticates to the token endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientAuth {
+    /// `Authorization: Basic` with the client id and secret.
+    #[default]
+    Basic,
+    /// `client_id` and `client_secret` form fields.
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthConfig {
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: Option<String>,
+    pub grant: Grant,
+    pub client_auth: ClientAuth,
+}
+
+impl OAuthConfig {
+    /// Requests with the same endpoint, client, scope and user share a token.
+    fn cache_key(&self) -> String {
+        let user = match &self.grant {
+            Grant::Password { username, .. } => username.as_str(),
+            _ => "",
+        };
+        format!(
+            "{}\n{}\n{}\n{}",
+            self.token_url,
+            self.client_id,
+            self.scope.as_deref().unwrap_or_default(),
+            user
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_at: Option<SystemTime>,
+    pub refresh_token: Option<String>,
+}
+
+impl Token {
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| now + EXPIRY_MARGIN < expires_at)
+    }
+
+    /// `Authorization` header value, e.g. `Bearer abc`.
+    pub fn authorization(&self) -> String {
+        let token_type = if self.token_type.eq_ignore_ascii_case("bearer") {
+            "Bearer"
+        } else {
+            &self.token_type
+        };
+        format!("{token_type} {}", self.access_token)
+    }
+}
+
+/// Token endpoint response (RFC 6749 section 5.1).
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+/// Error response (RFC 6749 section 5.2).
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// State of the cached token, shown under the auth panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenStatus {
+    Missing,
+    Valid(Option<Duration>),
+    Expired,
+}
+
+impl TokenStatus {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Missing => "no token yet, fetched on send".to_string(),
+            Self::Valid(None) => "token cached".to_string(),
+            Self::Valid(Some(left)) => format!("token valid for {}", format_remaining(*left)),
+            Self::Expired => "token expired, refreshed on send".to_string(),
+        }
+    }
+}
+
+/// `45s`, `12m` or `2h 5m`.
+fn format_remaining(left: Duration) -> String {
+    let seconds = left.as_secs();
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m", seconds / 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+pub fn token_status(config: &OAuthConfig) -> TokenStatus {
+    let tokens = TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    let now = SystemTime::now();
+    match tokens.get(&config.cache_key()) {
+        None => TokenStatus::Missing,
+        Some(token) if !token.is_fresh(now) => TokenStatus::Expired,
+        Some(token) => TokenStatus::Valid(
+            token
+                .expires_at
+                .and_then(|expires_at| expires_at.duration_since(now).ok()),
+        ),
+    }
+}
+
+/// The cached token, if it is still fresh.
+pub fn cached_token(config: &OAuthConfig) -> Option<Token> {
+    let tokens = TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    tokens
+        .get(&config.cache_key())
+        .filter(|token| token.is_fresh(SystemTime::now()))
+        .cloned()
+}
+
+/// Drops the cached token, e.g. after the API rejected it.
+pub fn forget_token(config: &OAuthConfig) {
+    let mut tokens = TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    tokens.remove(&config.cache_key());
+}
+
+/// A fresh access token: the cached one, a refreshed one when it expired
+/// and came with a refresh token, or a new one from the configured grant.
+pub fn access_token(
+    config: &OAuthConfig,
+    options: &SendOptions,
+    cancelled: &AtomicBool,
+) -> Result<Token, SendError> {
+    if let Some(token) = cached_token(config) {
+        return Ok(token);
+    }
+    let key = config.cache_key();
+    let stale = TOKENS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&key);
+    let refreshed = stale
+        .and_then(|token| token.refresh_token)
+        .and_then(|refresh_token| {
+            request_token(
+                config,
+                &Grant::RefreshToken { refresh_token },
+                options,
+                cancelled,
+            )
+            .ok()
+        });
+    let token = match refreshed {
+        Some(token) => token,
+        None => request_token(config, &config.grant, options, cancelled)?,
+    };
+    TOKENS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key, token.clone());
+    Ok(token)
+}
+
+fn request_token(
+    config: &OAuthConfig,
+    grant: &Grant,
+    options: &SendOptions,
+    cancelled: &AtomicBool,
+) -> Result<Token, SendError> {
+    let mut form = url::form_urlencoded::Serializer::new(String::new());
+    form.append_pair("grant_type", grant.grant_type());
+    match grant {
+        Grant::ClientCredentials => {}
+        Grant::Password { username, password } => {
+            form.append_pair("username", username);
+            form.append_pair("password", password);
+        }
+        Grant::RefreshToken { refresh_token } => {
+            form.append_pair("refresh_token", refresh_token);
+        }
+    }
+    if let Some(scope) = &config.scope {
+        form.append_pair("scope", scope);
+    }
+    let mut headers = vec![
+        (
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        ),
+        ("Accept".to_string(), "application/json".to_string()),
+    ];
+    match config.client_auth {
+        ClientAuth::Basic => headers.push((
+            "Authorization".to_string(),
+            format!(
+                "Basic {}",
+                STANDARD.encode(format!(
+                    "{}:{}",
+                    form_encode(&config.client_id),
+                    form_encode(&config.client_secret)
+                ))
+            ),
+        )),
+        ClientAuth::Body => {
+            form.append_pair("client_id", &config.client_id);
+            if !config.client_secret.is_empty() {
+                form.append_pair("client_secret", &config.client_secret);
+            }
+        }
+    }
+
+    let request = PreparedRequest {
+        method: Method::Post,
+        url: config.token_url.clone(),
+        query: Vec::new(),
+        headers,
+        body: Some(BodyPayload::Form(form.finish())),
+        brotli: false,
+        options: *options,
+        auth: Auth::None,
+    };
+    let response = request.send_once(cancelled)?.response;
+    let body = String::from_utf8_lossy(&response.body);
+    let status = response.status.as_u16();
+    if !(200..300).contains(&status) {
+        let reason = match serde_json::from_str::<TokenError>(&body) {
+            Ok(TokenError {
+                error,
+                error_description: Some(description),
+            }) => format!("{error}: {description}"),
+            Ok(TokenError { error, .. }) => error,
+            Err(_) => format!("HTTP {}", response.status),
+        };
+        return Err(SendError::Invalid(format!(
+            "Token request failed: {reason}"
+        )));
+    }
+    let parsed: TokenResponse = serde_json::from_str(&body)
+        .map_err(|e| SendError::Invalid(format!("Unexpected token response: {e}")))?;
+    Ok(Token {
+        access_token: parsed.access_token,
+        token_type: parsed.token_type.unwrap_or_else(|| "Bearer".to_string()),
+        expires_at: parsed
+            .expires_in
+            .map(|seconds| SystemTime::now() + Duration::from_secs(seconds)),
+        refresh_token: parsed.refresh_token.or(match grant {
+            Grant::RefreshToken { refresh_token } => Some(refresh_token.clone()),
+            _ => None,
+        }),
+    })
+}
+
+/// Client credentials are form-encoded before going into the Basic header
+/// (RFC 6749 section 2.3.1).
+fn form_encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex, atomic::AtomicBool},
+        thread,
+    };
+
+    use super::{ClientAuth, Grant, OAuthConfig, TokenStatus, access_token, token_status};
+    use crate::types::send_options::SendOptions;
+
+    /// A token endpoint on a local port that records the request bodies it
+    /// receives and answers each with the next canned response.
+    fn token_server(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let seen = bodies.clone();
+        thread::spawn(move || {
+            for (stream, body) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut request = vec![0; length];
+                reader.read_exact(&mut request).unwrap();
+                seen.lock()
+                    .unwrap()
+                    .push(String::from_utf8(request).unwrap());
+                let status = if body.contains("\"error\"") {
+                    "400 Bad Request"
+                } else {
+                    "200 OK"
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (url, bodies)
+    }
+
+    #[test]
+    fn fetches_caches_and_refreshes_tokens() {
+        let (token_url, bodies) = token_server(vec![
+            r#"{"access_token":"first","token_type":"bearer","expires_in":10,"refresh_token":"r1"}"#,
+            r#"{"access_token":"second","token_type":"bearer","expires_in":3600}"#,
+        ]);
+        let config = OAuthConfig {
+            token_url,
+            client_id: "parsel".to_string(),
+            client_secret: "s3cret".to_string(),
+            scope: Some("read".to_string()),
+            grant: Grant::ClientCredentials,
+            client_auth: ClientAuth::Body,
+        };
+        assert_eq!(token_status(&config), TokenStatus::Missing);
+
+        // Expires within the safety margin, so the next use refreshes it.
+        let token =
+            access_token(&config, &SendOptions::default(), &AtomicBool::new(false)).unwrap();
+        assert_eq!(token.authorization(), "Bearer first");
+        assert_eq!(token_status(&config), TokenStatus::Expired);
+
+        let token =
+            access_token(&config, &SendOptions::default(), &AtomicBool::new(false)).unwrap();
+        assert_eq!(token.access_token, "second");
+        assert_eq!(token.refresh_token.as_deref(), Some("r1"));
+        assert!(matches!(token_status(&config), TokenStatus::Valid(Some(_))));
+        let cached =
+            access_token(&config, &SendOptions::default(), &AtomicBool::new(false)).unwrap();
+        assert_eq!(cached.access_token, "second");
+
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(
+            bodies[0],
+            "grant_type=client_credentials&scope=read&client_id=parsel&client_secret=s3cret"
+        );
+        assert!(bodies[1].starts_with("grant_type=refresh_token&refresh_token=r1"));
+    }
+
+    #[test]
+    fn reports_token_endpoint_errors() {
+        let (token_url, _) = token_server(vec![
+            r#"{"error":"invalid_grant","error_description":"bad password"}"#,
+        ]);
+        let config = OAuthConfig {
+            token_url,
+            client_id: "parsel".to_string(),
+            client_secret: String::new(),
+            scope: None,
+            grant: Grant::Password {
+                username: "ada".to_string(),
+                password: "nope".to_string(),
+            },
+            client_auth: ClientAuth::Basic,
+        };
+        let err =
+            access_token(&config, &SendOptions::default(), &AtomicBool::new(false)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Token request failed: invalid_grant: bad password"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use tui_input::{Input, InputRequest};
+
+use crate::types::{app::App, collection::CollectionItem, response_file::SaveContent};
+
+/// What a submitted prompt value is used for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    SaveRequest,
+    RenameRequest(CollectionItem),
+    DeleteRequest(CollectionItem),
+    ImportCurl,
+    SaveResponse(SaveContent),
+    OverwriteResponse(SaveContent, String),
+    ResponseCharset,
+    /// New value for the cookie at this index of the jar, named for the title.
+    EditCookie(usize, String),
+    ClearCookies(usize),
+}
+
+impl PromptKind {
+    pub fn title(&self) -> String {
+        match self {
+            Self::SaveRequest => "Save request as (folder/name)".to_string(),
+            Self::RenameRequest(item) => format!("Rename {}", item.id()),
+            Self::DeleteRequest(item) => format!("Delete {}? (y/n)", item.id()),
+            Self::ImportCurl => "Paste a curl command".to_string(),
+            Self::SaveResponse(content) => format!("Save {} response body to", content.label()),
+            Self::OverwriteResponse(_, path) => format!("{path} exists. Overwrite? (y/n)"),
+            Self::ResponseCharset => {
+                "Decode responses as (e.g. windows-1252, empty to detect)".to_string()
+            }
+            Self::EditCookie(_, name) => format!("Value of cookie {name}"),
+            Self::ClearCookies(count) => format!("Delete all {count} cookies? (y/n)"),
+        }
+    }
+
+    /// Whether Enter after a trailing `\` keeps the prompt open, so a
+    /// multi-line shell command can be typed line by line.
+    pub fn allows_continuation(&self) -> bool {
+        matches!(self, Self::ImportCurl)
+    }
+}
+
+/// A single-line input shown on top of the UI until submitted or dismissed.
+#[derive(Debug)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: Input,
+}
+
+impl<'a> App<'a> {
+    pub(crate) fn open_prompt(&mut self, kind: PromptKind, initial: &str) {
+        self.prompt = Some(Prompt {
+            kind,
+            input: Input::new(initial.to_string()),
+        });
+    }
+
+    /// Inserts pasted text into the prompt, joining shell line continuations.
+    pub(crate) fn paste_into_prompt(&mut self, text: &str) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+        let text = text.replace("\\\r\n", " ").replace("\\\n", " ");
+        for ch in text.chars() {
+            let ch = if ch == '\n' || ch == '\r' { ' ' } else { ch };
+            prompt.input.handle(InputRequest::InsertChar(ch));
+        }
+    }
+
+    pub(crate) fn submit_prompt(&mut self) {
+        if let Some(prompt) = self.prompt.as_mut()
+            && prompt.kind.allows_continuation()
+            && prompt.input.value().trim_end().ends_with('\\')
+        {
+            let value = prompt.input.value().trim_end().trim_end_matches('\\');
+            prompt.input = Input::new(format!("{value} "));
+            return;
+        }
+
+        let Some(prompt) = self.prompt.take() else {
+            return;
+        };
+        let value = prompt.input.value().trim().to_string();
+        let result = match prompt.kind {
+            PromptKind::SaveRequest => self.save_request_as(&value),
+            PromptKind::RenameRequest(item) => self.rename_collection_item(&item, &value),
+            PromptKind::DeleteRequest(item) => {
+                if is_yes(&value) {
+                    self.delete_collection_item(&item)
+                } else {
+                    Ok(())
+                }
+            }
+            PromptKind::ImportCurl => self.import_curl(&value),
+            PromptKind::SaveResponse(content) => self.submit_save_response(content, &value),
+            PromptKind::OverwriteResponse(content, path) => {
+                if is_yes(&value) {
+                    self.save_response(content, &path)
+                } else {
+                    Ok(())
+                }
+            }
+            PromptKind::ResponseCharset => self.set_response_charset(&value),
+            PromptKind::EditCookie(index, _) => self.edit_cookie(index, &value),
+            PromptKind::ClearCookies(_) => {
+                if is_yes(&value) {
+                    self.clear_cookies()
+                } else {
+                    Ok(())
+                }
+            }
+        };
+        if let Err(message) = result {
+            self.app_state.error = Some(message);
+        }
+    }
+}
+
+fn is_yes(value: &str) -> bool {
+    value.eq_ignore_ascii_case("y") || value.eq_ignore_ascii_case("yes")
+}
@@ -0,0 +1,776 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use curl_rest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    app::{BodyMode, RawType, method_from_label, method_label},
+    auth::{Auth, DigestChallenge, new_cnonce},
+    cookies::CookieJar,
+    history::unix_timestamp,
+    json_body,
+    multipart::{self, Part},
+    oauth::{self, OAuthConfig},
+    send_options::{SendOptions, format_duration, is_idempotent},
+    transfer::{Received, Redirect, Transfer},
+};
+
+/// Everything needed to rebuild the request panels: the method label plus the
+/// raw text of each input, exactly as typed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestSpec {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub body_mode: BodyMode,
+    #[serde(default)]
+    pub raw_type: RawType,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub headers: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub options: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub auth: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyPayload {
+    Json(String),
+    Form(String),
+    Raw(String),
+    /// Parts are read and encoded when the request is sent.
+    Multipart {
+        parts: Vec<Part>,
+        boundary: String,
+    },
+}
+
+/// A request with every panel parsed and encoded, ready to be sent or
+/// rendered for another tool.
+#[derive(Debug, Clone)]
+pub struct PreparedRequest {
+    pub method: Method,
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<BodyPayload>,
+    pub brotli: bool,
+    pub options: SendOptions,
+    pub auth: Auth,
+}
+
+/// Why a request produced no response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendError {
+    /// No connection within the connect timeout.
+    ConnectTimeout(Duration),
+    /// No complete response within the total timeout.
+    Timeout(Duration),
+    /// The transfer failed; worth retrying for idempotent methods.
+    Transport(String),
+    /// The request cannot be sent as built.
+    Invalid(String),
+    /// Stopped by the user.
+    Cancelled,
+}
+
+impl SendError {
+    /// Timeouts are told apart by the transfer, which knows the limits.
+    pub(crate) fn from_curl(err: curl_rest::Error) -> Self {
+        match err {
+            curl_rest::Error::InvalidUrl(_)
+            | curl_rest::Error::InvalidHeaderValue(_)
+            | curl_rest::Error::InvalidHeaderName(_) => Self::Invalid(describe_error(err)),
+            err => Self::Transport(describe_error(err)),
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        !matches!(self, Self::Invalid(_) | Self::Cancelled)
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConnectTimeout(after) => {
+                write!(f, "Timed out connecting after {}", format_duration(*after))
+            }
+            Self::Timeout(after) => write!(
+                f,
+                "Timed out after {} waiting for the response",
+                format_duration(*after)
+            ),
+            Self::Transport(message) | Self::Invalid(message) => f.write_str(message),
+            Self::Cancelled => f.write_str("Cancelled"),
+        }
+    }
+}
+
+impl PreparedRequest {
+    /// Parses the panel inputs of an already resolved `spec`. Fails when the
+    /// body cannot be encoded.
+    pub fn from_spec(spec: &RequestSpec) -> Result<Self, String> {
+        let mut query = parse_key_value_lines(&spec.query);
+        let mut headers = parse_key_value_lines(&spec.headers);
+        let auth = Auth::parse(&spec.auth)?;
+        auth.apply(&mut headers, &mut query);
+        let pairs = parse_key_value_lines(&spec.body);
+        let body = match spec.body_mode {
+            BodyMode::Json => {
+                json_body::build(pairs)?.map(|value| BodyPayload::Json(value.to_string()))
+            }
+            BodyMode::Form => {
+                let encoded = form_encode(pairs);
+                if encoded.is_empty() {
+                    None
+                } else {
+                    Some(BodyPayload::Form(encoded))
+                }
+            }
+            BodyMode::Raw => {
+                if spec.body.trim().is_empty() {
+                    None
+                } else {
+                    if spec.raw_type == RawType::Json {
+                        validate_json(&spec.body)
+                            .map_err(|e| format!("Request body is not valid JSON: {e}"))?;
+                    }
+                    Some(BodyPayload::Raw(spec.body.clone()))
+                }
+            }
+            BodyMode::Multipart => {
+                let parts = multipart::parse_parts(pairs);
+                if parts.is_empty() {
+                    None
+                } else {
+                    multipart::validate(&parts)?;
+                    Some(BodyPayload::Multipart {
+                        parts,
+                        boundary: multipart::new_boundary(),
+                    })
+                }
+            }
+        };
+
+        let default_content_type = match &body {
+            Some(BodyPayload::Form(_)) => Some("application/x-www-form-urlencoded"),
+            Some(BodyPayload::Raw(_)) => spec.raw_type.content_type(),
+            _ => None,
+        };
+        if let Some(content_type) = default_content_type
+            && !has_header(&headers, "content-type")
+        {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+
+        let brotli = should_enable_brotli(&headers);
+        let options = SendOptions::parse(&spec.options)?;
+
+        Ok(Self {
+            method: method_from_label(&spec.method).unwrap_or_default(),
+            url: spec.url.clone(),
+            query,
+            headers,
+            body,
+            brotli,
+            options,
+            auth,
+        })
+    }
+
+    /// Headers as they go on the wire, including the content type that
+    /// `curl_rest` adds when none is set. The multipart content type is left
+    /// out since every tool generates its own boundary.
+    pub fn effective_headers(&self) -> Vec<(String, String)> {
+        let mut headers = self.headers.clone();
+        let default_content_type = match &self.body {
+            Some(BodyPayload::Json(_)) => Some("application/json"),
+            Some(BodyPayload::Form(_)) | Some(BodyPayload::Raw(_)) => {
+                Some("text/plain; charset=utf-8")
+            }
+            Some(BodyPayload::Multipart { .. }) | None => None,
+        };
+        if let Some(content_type) = default_content_type
+            && !has_header(&headers, "content-type")
+        {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+        headers
+    }
+
+    pub fn body_text(&self) -> Option<&str> {
+        match &self.body {
+            Some(BodyPayload::Json(body))
+            | Some(BodyPayload::Form(body))
+            | Some(BodyPayload::Raw(body)) => Some(body),
+            Some(BodyPayload::Multipart { .. }) | None => None,
+        }
+    }
+
+    /// The URL with query params appended the way `curl_rest` does it.
+    pub fn full_url(&self) -> String {
+        if self.query.is_empty() {
+            return self.url.clone();
+        }
+        let (base, fragment) = match self.url.split_once('#') {
+            Some((base, fragment)) => (base, Some(fragment)),
+            None => (self.url.as_str(), None),
+        };
+        let mut out = base.to_string();
+        if base.contains('?') {
+            if !base.ends_with('?') && !base.ends_with('&') {
+                out.push('&');
+            }
+        } else {
+            out.push('?');
+        }
+        let query = self
+            .query
+            .iter()
+            .map(|(key, value)| format!("{}={}", percent_encode(key), percent_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        out.push_str(&query);
+        if let Some(fragment) = fragment {
+            out.push('#');
+            out.push_str(fragment);
+        }
+        out
+    }
+
+    /// Builds the transfer to hand to libcurl, reading any files a multipart
+    /// body uploads.
+    pub fn transfer(&self) -> Result<Transfer, String> {
+        let mut headers = self.effective_headers();
+        let mut multipart_body = None;
+        if let Some(BodyPayload::Multipart { parts, boundary }) = &self.body {
+            let content_type = format!("multipart/form-data; boundary={boundary}");
+            match headers
+                .iter_mut()
+                .find(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+            {
+                // A bare `multipart/form-data` header is useless without the boundary.
+                Some((_, value)) if !value.contains("boundary=") => *value = content_type,
+                Some(_) => {}
+                None => headers.push(("Content-Type".to_string(), content_type)),
+            }
+            multipart_body = Some(multipart::encode(parts, boundary)?);
+        }
+        // Signed last, over the headers and body exactly as they are sent.
+        let body = multipart_body
+            .unwrap_or_else(|| self.body_text().unwrap_or_default().as_bytes().to_vec());
+        let method = method_label(&self.method);
+        match &self.auth {
+            Auth::AwsSigV4(config) if !has_header(&headers, "authorization") => {
+                config.sign(
+                    method,
+                    &self.full_url(),
+                    &mut headers,
+                    &body,
+                    SystemTime::now(),
+                )?;
+            }
+            Auth::Hmac(config) if !has_header(&headers, &config.header) => {
+                config.sign(
+                    method,
+                    &self.full_url(),
+                    &mut headers,
+                    &body,
+                    SystemTime::now(),
+                )?;
+            }
+            _ => {}
+        }
+        Ok(Transfer {
+            method: self.method.clone(),
+            url: self.full_url(),
+            headers,
+            body: self.body.is_some().then_some(body),
+            brotli: self.brotli,
+            options: self.options,
+        })
+    }
+
+    /// Sends the request, retrying failed transfers of idempotent methods
+    /// with backoff. The cookies of `jar` go with every hop, along with the
+    /// ones set by the redirects before it; the caller stores them all from
+    /// the result. Setting `cancelled` aborts the transfer under way and
+    /// skips any retries left.
+    pub fn send(&self, jar: &CookieJar, cancelled: &AtomicBool) -> Result<Received, SendError> {
+        let attempts = if is_idempotent(&self.method) {
+            self.options.retries + 1
+        } else {
+            1
+        };
+        let mut attempt = 1;
+        loop {
+            match self.send_following(jar, cancelled) {
+                Err(err) if err.is_retryable() && attempt < attempts => {
+                    let deadline = Instant::now() + self.options.backoff(attempt);
+                    while Instant::now() < deadline {
+                        if cancelled.load(Ordering::Relaxed) {
+                            return Err(err);
+                        }
+                        thread::sleep(Duration::from_millis(20));
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// One attempt, following redirects as the options panel allows. The
+    /// total timeout covers every hop.
+    fn send_following(
+        &self,
+        jar: &CookieJar,
+        cancelled: &AtomicBool,
+    ) -> Result<Received, SendError> {
+        let started = Instant::now();
+        let mut jar = jar.clone();
+        let mut request = self.clone();
+        let mut redirects = Vec::new();
+        loop {
+            let mut hop = request.clone();
+            if let Some(total) = self.options.timeout {
+                let left = total.saturating_sub(started.elapsed());
+                if left.is_zero() {
+                    return Err(SendError::Timeout(total));
+                }
+                hop.options.timeout = Some(left);
+            }
+            jar.apply(&mut hop, unix_timestamp());
+            let mut received = hop.send_authenticated(cancelled).map_err(|err| match err {
+                SendError::Timeout(_) => {
+                    SendError::Timeout(self.options.timeout.unwrap_or_else(|| started.elapsed()))
+                }
+                err => err,
+            })?;
+            jar.store_response(&received.url, &received.response.headers, unix_timestamp());
+            let next = match request.redirected(&received) {
+                Some(next) if self.options.follow_redirects => next,
+                _ => {
+                    received.redirects = redirects;
+                    return Ok(received);
+                }
+            };
+            if redirects.len() >= self.options.max_redirects as usize {
+                return Err(SendError::Transport(format!(
+                    "Maximum ({}) redirects followed",
+                    self.options.max_redirects
+                )));
+            }
+            redirects.push(Redirect {
+                url: received.url,
+                headers: received.response.headers,
+            });
+            request = next;
+        }
+    }
+
+    /// The request to send for a redirect response, changed the way curl
+    /// changes it: a 303, or a 301/302 after a POST, becomes a GET without
+    /// a body, and credentials are dropped when the host changes.
+    fn redirected(&self, received: &Received) -> Option<Self> {
+        let status = received.response.status.as_u16();
+        if !matches!(status, 301 | 302 | 303 | 307 | 308) {
+            return None;
+        }
+        let location = received
+            .response
+            .headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case("location"))?;
+        let from = url::Url::parse(&received.url).ok()?;
+        let mut to = from.join(location.value.trim()).ok()?;
+        to.set_fragment(None);
+
+        let mut next = self.clone();
+        if (status == 303 && !matches!(self.method, Method::Head))
+            || (matches!(status, 301 | 302) && matches!(self.method, Method::Post))
+        {
+            next.method = Method::Get;
+            next.body = None;
+            next.headers
+                .retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+        }
+        if (to.host_str(), to.port_or_known_default())
+            != (from.host_str(), from.port_or_known_default())
+        {
+            next.auth = Auth::None;
+            next.headers.retain(|(name, _)| {
+                !name.eq_ignore_ascii_case("authorization") && !name.eq_ignore_ascii_case("cookie")
+            });
+        }
+        next.url = to.to_string();
+        next.query.clear();
+        Some(next)
+    }
+
+    /// One hop, answering a Digest challenge with a second request when
+    /// the auth panel asks for Digest.
+    fn send_authenticated(&self, cancelled: &AtomicBool) -> Result<Received, SendError> {
+        if let Auth::OAuth2(config) = &self.auth {
+            return self.send_with_token(config, cancelled);
+        }
+        let received = self.send_once(cancelled)?;
+        let Auth::Digest { username, password } = &self.auth else {
+            return Ok(received);
+        };
+        if received.response.status.as_u16() != 401 {
+            return Ok(received);
+        }
+        let Some(challenge) = received
+            .response
+            .headers
+            .iter()
+            .filter(|header| header.name.eq_ignore_ascii_case("www-authenticate"))
+            .find_map(|header| DigestChallenge::parse(&header.value))
+        else {
+            return Ok(received);
+        };
+        let url = url::Url::parse(&self.full_url())
+            .map_err(|e| SendError::Invalid(format!("Invalid URL: {e}")))?;
+        let uri = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        let authorization = challenge
+            .authorization(
+                username,
+                password,
+                method_label(&self.method),
+                &uri,
+                &new_cnonce(),
+            )
+            .map_err(SendError::Invalid)?;
+        let mut retry = self.clone();
+        retry
+            .headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("authorization"));
+        retry
+            .headers
+            .push(("Authorization".to_string(), authorization));
+        retry.send_once(cancelled)
+    }
+
+    /// Sends with an OAuth 2.0 access token, fetching one first when the
+    /// cache has none. A 401 on a cached token drops it and tries once
+    /// more with a fresh one.
+    fn send_with_token(
+        &self,
+        config: &OAuthConfig,
+        cancelled: &AtomicBool,
+    ) -> Result<Received, SendError> {
+        if has_header(&self.headers, "authorization") {
+            return self.send_once(cancelled);
+        }
+        let was_cached = oauth::cached_token(config).is_some();
+        let received = self.with_token(config, cancelled)?.send_once(cancelled)?;
+        if received.response.status.as_u16() != 401 || !was_cached {
+            return Ok(received);
+        }
+        oauth::forget_token(config);
+        self.with_token(config, cancelled)?.send_once(cancelled)
+    }
+
+    fn with_token(&self, config: &OAuthConfig, cancelled: &AtomicBool) -> Result<Self, SendError> {
+        let token = oauth::access_token(config, &self.options, cancelled)?;
+        let mut request = self.clone();
+        request
+            .headers
+            .push(("Authorization".to_string(), token.authorization()));
+        Ok(request)
+    }
+
+    /// One attempt, within the timeouts of the options panel.
+    pub(crate) fn send_once(&self, cancelled: &AtomicBool) -> Result<Received, SendError> {
+        self.transfer()
+            .map_err(SendError::Invalid)?
+            .perform(cancelled)
+    }
+}
+
+pub fn describe_error(err: curl_rest::Error) -> String {
+    match err {
+        curl_rest::Error::Client(error) => {
+            format!("Curl error {}", error)
+        }
+        curl_rest::Error::InvalidUrl(url) => {
+            format!("The provided url: {url} is invalid.")
+        }
+        curl_rest::Error::InvalidHeaderValue(v) => {
+            format!("Invalid header value: {v} supplied.")
+        }
+        curl_rest::Error::InvalidHeaderName(n) => {
+            format!("Invalid header name: {n}.")
+        }
+        curl_rest::Error::InvalidStatusCode(c) => {
+            format!("Invalid status code: {c}, supplied.")
+        }
+        curl_rest::Error::BrotliDecompression(e) => {
+            format!("Failed to deceompress brotli: {e}")
+        }
+    }
+}
+
+pub(crate) fn parse_key_value_lines(input: &str) -> Vec<(String, String)> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            let value = value.trim();
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn form_encode(pairs: Vec<(String, String)>) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    for (key, value) in pairs {
+        serializer.append_pair(&key, &value);
+    }
+    serializer.finish()
+}
+
+/// Checks that a raw body parses as JSON. An empty body is accepted.
+pub(crate) fn validate_json(body: &str) -> Result<(), String> {
+    if body.trim().is_empty() {
+        return Ok(());
+    }
+    serde_json::from_str::<serde_json::Value>(body)
+        .map(|_| ())
+        .map_err(|e| describe_json_error(&e))
+}
+
+pub(crate) fn pretty_json(body: &str) -> Result<String, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| describe_json_error(&e))?;
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+fn describe_json_error(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    let message = match message.rfind(" at line ") {
+        Some(idx) => &message[..idx],
+        None => &message,
+    };
+    format!("line {}, column {}: {message}", err.line(), err.column())
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() {
+                (byte as char).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn has_header(headers: &[(String, String)], name: &str) -> bool {
+    headers
+        .iter()
+        .any(|(key, _)| key.eq_ignore_ascii_case(name))
+}
+
+pub(crate) fn should_enable_brotli(headers: &[(String, String)]) -> bool {
+    let mut best_encoding: Option<(&str, f32, usize)> = None;
+    let mut index = 0usize;
+
+    for (key, value) in headers {
+        if !key.eq_ignore_ascii_case("accept-encoding") {
+            continue;
+        }
+
+        for token in value.split(',') {
+            let mut parts = token.split(';');
+            let encoding = parts.next().map(str::trim).unwrap_or_default();
+            if encoding.is_empty() {
+                continue;
+            }
+
+            let mut q = 1.0f32;
+            for param in parts {
+                let Some((name, raw_value)) = param.split_once('=') else {
+                    continue;
+                };
+                if !name.trim().eq_ignore_ascii_case("q") {
+                    continue;
+                }
+
+                let Ok(parsed) = raw_value.trim().parse::<f32>() else {
+                    q = -1.0;
+                    break;
+                };
+                if !parsed.is_finite() || !(0.0..=1.0).contains(&parsed) {
+                    q = -1.0;
+                    break;
+                }
+                q = parsed;
+                break;
+            }
+
+            if q < 0.0 {
+                continue;
+            }
+
+            match best_encoding {
+                Some((_, best_q, best_idx)) if q < best_q || (q == best_q && index > best_idx) => {}
+                _ => best_encoding = Some((encoding, q, index)),
+            }
+
+            index += 1;
+        }
+    }
+
+    matches!(best_encoding, Some((encoding, q, _)) if q > 0.0 && encoding.eq_ignore_ascii_case("br"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::atomic::AtomicBool,
+        thread,
+    };
+
+    use super::{BodyPayload, PreparedRequest, RequestSpec, pretty_json, validate_json};
+    use crate::types::{
+        app::{BodyMode, RawType},
+        cookies::CookieJar,
+    };
+
+    /// Answers a login with a redirect that sets a session cookie, then the
+    /// redirected request with the `Cookie` header it got. Returns the
+    /// request lines received.
+    fn login_server() -> (u16, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let mut request_lines = Vec::new();
+            for response in ["302 Found", "200 OK"] {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut head = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    head.push(line.trim().to_string());
+                }
+                let header = |name: &str| {
+                    head.iter().find_map(|line| {
+                        let (key, value) = line.split_once(':')?;
+                        key.eq_ignore_ascii_case(name)
+                            .then(|| value.trim().to_string())
+                    })
+                };
+                let length = header("content-length").map_or(0, |len| len.parse().unwrap());
+                reader.read_exact(&mut vec![0; length]).unwrap();
+                let body = header("cookie").unwrap_or_default();
+                let extra = if response.starts_with("302") {
+                    "Set-Cookie: sid=abc\r\nLocation: /auth/me\r\n"
+                } else {
+                    ""
+                };
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {response}\r\n{extra}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                request_lines.push(head[0].clone());
+            }
+            request_lines
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn raw_body_is_sent_verbatim_with_selected_content_type() {
+        let prepared = PreparedRequest::from_spec(&RequestSpec {
+            method: "POST".to_string(),
+            url: "https://example.com".to_string(),
+            body_mode: BodyMode::Raw,
+            raw_type: RawType::Xml,
+            body: "<a>1</a>".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            prepared.body,
+            Some(BodyPayload::Raw("<a>1</a>".to_string()))
+        );
+        assert_eq!(
+            prepared.headers,
+            vec![("Content-Type".to_string(), "application/xml".to_string())]
+        );
+    }
+
+    #[test]
+    fn json_validation_reports_position_and_formatting_keeps_key_order() {
+        assert_eq!(
+            validate_json("{\"a\": 1,\n \"b\": }"),
+            Err("line 2, column 7: expected value".to_string())
+        );
+        assert_eq!(
+            pretty_json("{\"z\":1,\"a\":[true]}").unwrap(),
+            "{\n  \"z\": 1,\n  \"a\": [\n    true\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn redirects_carry_the_cookies_set_along_the_way() {
+        let (port, server) = login_server();
+        let prepared = PreparedRequest::from_spec(&RequestSpec {
+            method: "POST".to_string(),
+            url: format!("http://127.0.0.1:{port}/auth/login"),
+            body_mode: BodyMode::Form,
+            body: "user: ada".to_string(),
+            options: "follow_redirects: true".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let received = prepared
+            .send(&CookieJar::default(), &AtomicBool::new(false))
+            .unwrap();
+        assert_eq!(
+            server.join().unwrap(),
+            ["POST /auth/login HTTP/1.1", "GET /auth/me HTTP/1.1"]
+        );
+        assert_eq!(received.response.body, b"sid=abc");
+        assert_eq!(received.url, format!("http://127.0.0.1:{port}/auth/me"));
+
+        let mut jar = CookieJar::default();
+        assert_eq!(jar.store_received(&received, 0), 1);
+        let cookie = &jar.cookies()[0];
+        assert_eq!(
+            (cookie.domain.as_str(), cookie.path.as_str()),
+            ("127.0.0.1", "/auth")
+        );
+    }
+}
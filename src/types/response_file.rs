@@ -0,0 +1,228 @@
+use std::{fs, path::Path};
+
+use percent_encoding::percent_decode_str;
+
+use crate::types::{app::App, binary::format_size, prompt::PromptKind};
+
+/// Name suggested when neither the headers nor the URL provide one.
+const DEFAULT_NAME: &str = "response";
+
+/// What gets written when saving a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveContent {
+    /// The body exactly as received.
+    Raw,
+    /// The text shown in the response panel: pretty-printed, filtered, or
+    /// the hex dump of a binary body.
+    Formatted,
+}
+
+impl SaveContent {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Formatted => "formatted",
+        }
+    }
+}
+
+/// File name for a response: the `Content-Disposition` filename, else the
+/// last URL path segment, else `response`. A name without an extension
+/// gets one from the Content-Type.
+pub fn suggest_filename(
+    headers: Option<&str>,
+    url: Option<&str>,
+    content_type: Option<&str>,
+) -> String {
+    let name = headers
+        .and_then(|headers| header_value(headers, "content-disposition"))
+        .and_then(disposition_filename)
+        .or_else(|| url.and_then(url_filename))
+        .unwrap_or_else(|| DEFAULT_NAME.to_string());
+    if name.contains('.') {
+        return name;
+    }
+    match content_type.and_then(extension_for) {
+        Some(extension) => format!("{name}.{extension}"),
+        None => name,
+    }
+}
+
+fn header_value<'h>(headers: &'h str, name: &str) -> Option<&'h str> {
+    headers.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+/// `filename*=UTF-8''...` (RFC 6266) wins over a plain `filename=`.
+fn disposition_filename(value: &str) -> Option<String> {
+    let params: Vec<(String, &str)> = value
+        .split(';')
+        .skip(1)
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((key.trim().to_ascii_lowercase(), value.trim()))
+        })
+        .collect();
+    let extended = params
+        .iter()
+        .find(|(key, _)| key == "filename*")
+        .and_then(|(_, value)| {
+            let (charset, rest) = value.split_once('\'')?;
+            let (_, encoded) = rest.split_once('\'')?;
+            let bytes: Vec<u8> = percent_decode_str(encoded).collect();
+            if charset.eq_ignore_ascii_case("utf-8") {
+                String::from_utf8(bytes).ok()
+            } else {
+                Some(bytes.iter().map(|&byte| byte as char).collect())
+            }
+        });
+    extended
+        .or_else(|| {
+            params
+                .iter()
+                .find(|(key, _)| key == "filename")
+                .map(|(_, value)| value.trim_matches('"').replace("\\\"", "\""))
+        })
+        .and_then(|name| safe_name(&name))
+}
+
+fn url_filename(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let segment = url.path_segments()?.rfind(|segment| !segment.is_empty())?;
+    safe_name(&percent_decode_str(segment).decode_utf8_lossy())
+}
+
+/// Keeps only the final path component, so a server cannot pick the
+/// directory the file lands in.
+fn safe_name(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next()?;
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let name = name.trim();
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+fn extension_for(content_type: &str) -> Option<&'static str> {
+    let essence = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    let (kind, subtype) = essence.split_once('/')?;
+    let subtype = subtype.rsplit('+').next().unwrap_or(subtype);
+    Some(match (kind, subtype) {
+        (_, "json") => "json",
+        (_, "xml") => "xml",
+        (_, "yaml" | "x-yaml") => "yaml",
+        (_, "javascript" | "x-javascript" | "ecmascript") => "js",
+        ("text", "html") => "html",
+        ("text", "css") => "css",
+        ("text", "csv") => "csv",
+        ("text", "markdown") => "md",
+        ("text", _) => "txt",
+        ("image", "png") => "png",
+        ("image", "jpeg") => "jpg",
+        ("image", "gif") => "gif",
+        ("image", "webp") => "webp",
+        ("image", "svg") => "svg",
+        (_, "pdf") => "pdf",
+        (_, "zip") => "zip",
+        (_, "gzip" | "x-gzip") => "gz",
+        _ => return None,
+    })
+}
+
+impl<'a> App<'a> {
+    /// Asks where to save the response, suggesting a file name.
+    pub(crate) fn prompt_save_response(&mut self, content: SaveContent) {
+        if self.app_state.response_bytes.is_none() {
+            self.app_state.status_message = Some("No response to save".to_string());
+            return;
+        }
+        let name = suggest_filename(
+            self.app_state.response_headers.as_deref(),
+            self.app_state.request_url.as_deref(),
+            self.app_state.response_content_type.as_deref(),
+        );
+        self.open_prompt(PromptKind::SaveResponse(content), &name);
+    }
+
+    /// Saves to `path`, asking first when it would replace a file.
+    pub(crate) fn submit_save_response(
+        &mut self,
+        content: SaveContent,
+        path: &str,
+    ) -> Result<(), String> {
+        if path.is_empty() {
+            return Err("File path cannot be empty".to_string());
+        }
+        if Path::new(path).exists() {
+            self.open_prompt(PromptKind::OverwriteResponse(content, path.to_string()), "");
+            return Ok(());
+        }
+        self.save_response(content, path)
+    }
+
+    /// Writes the response to `path`.
+    pub(crate) fn save_response(&mut self, content: SaveContent, path: &str) -> Result<(), String> {
+        let state = &self.app_state;
+        let bytes = match content {
+            SaveContent::Raw => state.response_bytes.as_deref(),
+            SaveContent::Formatted => state
+                .response_formatted_body
+                .as_deref()
+                .or(state.response_body.as_deref())
+                .map(str::as_bytes),
+        };
+        let Some(bytes) = bytes else {
+            return Err("No response to save".to_string());
+        };
+        fs::write(path, bytes).map_err(|e| format!("Failed to write {path}: {e}"))?;
+        self.app_state.status_message = Some(format!(
+            "Wrote {} bytes ({}) to {path}",
+            bytes.len(),
+            format_size(bytes.len())
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suggest_filename;
+
+    #[test]
+    fn suggests_a_name_from_disposition_or_url() {
+        let headers = "Content-Type: application/pdf\n\
+                       Content-Disposition: attachment; filename=\"report.pdf\"";
+        assert_eq!(
+            suggest_filename(Some(headers), Some("https://x.test/dl"), None),
+            "report.pdf"
+        );
+        let headers = "content-disposition: attachment; filename=\"a.txt\"; \
+                       filename*=UTF-8''caf%C3%A9%20menu.txt";
+        assert_eq!(suggest_filename(Some(headers), None, None), "café menu.txt");
+        let headers = "Content-Disposition: attachment; filename=\"../../etc/passwd\"";
+        assert_eq!(suggest_filename(Some(headers), None, None), "passwd");
+
+        assert_eq!(
+            suggest_filename(
+                None,
+                Some("https://x.test/api/users/?page=2"),
+                Some("application/json; charset=utf-8")
+            ),
+            "users.json"
+        );
+        assert_eq!(
+            suggest_filename(None, Some("https://x.test/img/a%20b.png"), None),
+            "a b.png"
+        );
+        assert_eq!(
+            suggest_filename(None, Some("https://x.test/"), Some("text/html")),
+            "response.html"
+        );
+    }
+}
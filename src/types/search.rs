@@ -0,0 +1,257 @@
+use std::ops::Range;
+
+use regex::RegexBuilder;
+use tui_input::Input;
+
+use crate::types::app::{ActivePanel, App};
+
+/// One hit in the response display text: a line index and the byte range of
+/// the match within that line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub range: Range<usize>,
+}
+
+/// State of the `/` search over the response body. The query itself lives
+/// in `App::search_input`.
+#[derive(Debug, Default)]
+pub struct ResponseSearch {
+    pub editing: bool,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub matches: Vec<SearchMatch>,
+    pub current: usize,
+    pub error: Option<String>,
+}
+
+impl ResponseSearch {
+    pub fn current_match(&self) -> Option<&SearchMatch> {
+        self.matches.get(self.current)
+    }
+}
+
+/// Finds every non-empty match of `query` line by line. Plain queries are
+/// matched literally; both kinds ignore case unless `case_sensitive`.
+pub fn find_matches(
+    content: &str,
+    query: &str,
+    regex: bool,
+    case_sensitive: bool,
+) -> Result<Vec<SearchMatch>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = if regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let matcher = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| {
+            // The full message repeats the pattern with a caret; keep the
+            // final "error: …" line for the one-line panel title.
+            let message = e.to_string();
+            let reason = message.lines().last().unwrap_or_default();
+            format!("Invalid regex: {}", reason.trim_start_matches("error: "))
+        })?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .flat_map(|(line, text)| {
+            matcher
+                .find_iter(text)
+                .filter(|found| !found.is_empty())
+                .map(move |found| SearchMatch {
+                    line,
+                    range: found.range(),
+                })
+        })
+        .collect())
+}
+
+impl<'a> App<'a> {
+    /// Text shown in the response panel, which is what search runs over.
+    pub(crate) fn response_display_text(&self) -> Option<&str> {
+        self.app_state
+            .response_formatted_body
+            .as_deref()
+            .or(self.app_state.response_body.as_deref())
+    }
+
+    pub(crate) fn start_search(&mut self) {
+        self.app_state.active_panel = ActivePanel::ResBody;
+        self.app_state.tree_view = false;
+        self.search_input = Input::default();
+        self.app_state.search.editing = true;
+        self.refresh_search();
+    }
+
+    /// Re-runs the query after an edit and jumps to the first match at or
+    /// below the top of the viewport.
+    pub(crate) fn update_search(&mut self) {
+        self.refresh_search();
+        let top = self.app_state.response_scroll as usize;
+        let search = &mut self.app_state.search;
+        search.current = search
+            .matches
+            .iter()
+            .position(|found| found.line >= top)
+            .unwrap_or(0);
+        self.scroll_to_current_match();
+    }
+
+    /// Recomputes matches against the current display text, keeping the
+    /// selected match index when it is still in range.
+    pub(crate) fn refresh_search(&mut self) {
+        let result = find_matches(
+            self.response_display_text().unwrap_or_default(),
+            self.search_input.value(),
+            self.app_state.search.regex,
+            self.app_state.search.case_sensitive,
+        );
+        let search = &mut self.app_state.search;
+        match result {
+            Ok(matches) => {
+                search.matches = matches;
+                search.error = None;
+            }
+            Err(message) => {
+                search.matches.clear();
+                search.error = Some(message);
+            }
+        }
+        if search.current >= search.matches.len() {
+            search.current = 0;
+        }
+    }
+
+    pub(crate) fn finish_search(&mut self) {
+        self.app_state.search.editing = false;
+        if self.search_input.value().is_empty() {
+            self.clear_search();
+        }
+    }
+
+    pub(crate) fn clear_search(&mut self) {
+        self.search_input = Input::default();
+        self.app_state.search.editing = false;
+        self.app_state.search.matches.clear();
+        self.app_state.search.current = 0;
+        self.app_state.search.error = None;
+    }
+
+    pub(crate) fn toggle_search_regex(&mut self) {
+        self.app_state.search.regex = !self.app_state.search.regex;
+        self.update_search();
+    }
+
+    pub(crate) fn toggle_search_case(&mut self) {
+        self.app_state.search.case_sensitive = !self.app_state.search.case_sensitive;
+        self.update_search();
+    }
+
+    pub(crate) fn next_match(&mut self) {
+        let search = &mut self.app_state.search;
+        if search.matches.is_empty() {
+            return;
+        }
+        search.current = (search.current + 1) % search.matches.len();
+        self.scroll_to_current_match();
+    }
+
+    pub(crate) fn prev_match(&mut self) {
+        let search = &mut self.app_state.search;
+        if search.matches.is_empty() {
+            return;
+        }
+        search.current = search
+            .current
+            .checked_sub(1)
+            .unwrap_or(search.matches.len() - 1);
+        self.scroll_to_current_match();
+    }
+
+    /// "match 3/17" for the status bar while a query is set.
+    pub(crate) fn search_status(&self) -> Option<String> {
+        if self.search_input.value().is_empty() {
+            return None;
+        }
+        let search = &self.app_state.search;
+        Some(if search.matches.is_empty() {
+            "no matches".to_string()
+        } else {
+            format!("match {}/{}", search.current + 1, search.matches.len())
+        })
+    }
+
+    /// Scrolls both axes just enough to bring the selected match into view.
+    fn scroll_to_current_match(&mut self) {
+        let Some(found) = self.app_state.search.current_match().cloned() else {
+            return;
+        };
+        let Some(line) = self
+            .response_display_text()
+            .and_then(|text| text.lines().nth(found.line))
+        else {
+            return;
+        };
+        let start = line[..found.range.start].chars().count();
+        let end = start + line[found.range.clone()].chars().count();
+
+        let state = &mut self.app_state;
+        let height = state.response_viewport_height.max(1) as usize;
+        let top = state.response_scroll as usize;
+        if found.line < top || found.line >= top + height {
+            state.response_scroll =
+                found.line.saturating_sub(height / 3).min(u16::MAX as usize) as u16;
+        }
+
+        let width = state.response_viewport_width.max(1) as usize;
+        let left = state.response_scroll_x as usize;
+        if start < left || end > left + width {
+            let margin = if end - start < width { width / 4 } else { 0 };
+            state.response_scroll_x = start.saturating_sub(margin).min(u16::MAX as usize) as u16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchMatch, find_matches};
+
+    #[test]
+    fn plain_search_is_literal_and_ignores_case_by_default() {
+        let content = "{\n  \"Name\": \"a.b\",\n  \"name\": \"axb\"\n}";
+        let matches = find_matches(content, "NAME", false, false).unwrap();
+        assert_eq!(
+            matches,
+            vec![
+                SearchMatch {
+                    line: 1,
+                    range: 3..7
+                },
+                SearchMatch {
+                    line: 2,
+                    range: 3..7
+                },
+            ]
+        );
+        assert_eq!(find_matches(content, "a.b", false, false).unwrap().len(), 1);
+        assert_eq!(find_matches(content, "NAME", false, true).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn regex_search_reports_invalid_patterns() {
+        let content = "id: 12\nid: 345";
+        let matches = find_matches(content, r"\d+", true, false).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[1].range, 4..7);
+        assert_eq!(
+            find_matches(content, "(", true, false),
+            Err("Invalid regex: unclosed group".to_string())
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use curl_rest::Method;
+
+use crate::types::request::parse_key_value_lines;
+
+/// Keys accepted by the options panel, shown as its placeholder.
+pub const OPTION_KEYS: &[&str] = &[
+    "timeout: 30s",
+    "connect_timeout: 5s",
+    "follow_redirects: true",
+    "max_redirects: 10",
+    "retries: 2",
+    "retry_backoff: 500ms",
+];
+
+/// How a request is sent, parsed from the options panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendOptions {
+    /// Limit on resolving the host and connecting to it.
+    pub connect_timeout: Option<Duration>,
+    /// Limit on the whole exchange, retries excluded.
+    pub timeout: Option<Duration>,
+    pub follow_redirects: bool,
+    pub max_redirects: u8,
+    /// Extra attempts after a failed transfer; only used for idempotent
+    /// methods.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each following one.
+    pub retry_backoff: Duration,
+}
+
+impl Default for SendOptions {
+    /// One redirect followed and no timeouts, like `curl_rest`.
+    fn default() -> Self {
+        Self {
+            connect_timeout: None,
+            timeout: None,
+            follow_redirects: true,
+            max_redirects: 1,
+            retries: 0,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl SendOptions {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut options = Self::default();
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if !line.contains(':') {
+                return Err(format!("Expected `key: value`, got `{line}`"));
+            }
+        }
+        for (key, value) in parse_key_value_lines(input) {
+            let invalid = |expected: &str| format!("`{key}` expects {expected}, got `{value}`");
+            match key.to_ascii_lowercase().replace('-', "_").as_str() {
+                "timeout" => {
+                    options.timeout = parse_optional_duration(&value)
+                        .ok_or_else(|| invalid("a duration like 30s or 500ms"))?
+                }
+                "connect_timeout" => {
+                    options.connect_timeout = parse_optional_duration(&value)
+                        .ok_or_else(|| invalid("a duration like 5s or 500ms"))?
+                }
+                "follow_redirects" => {
+                    options.follow_redirects =
+                        parse_bool(&value).ok_or_else(|| invalid("true or false"))?
+                }
+                "max_redirects" => {
+                    options.max_redirects = value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|max| *max <= i8::MAX as u8)
+                        .ok_or_else(|| invalid("a number from 0 to 127"))?
+                }
+                "retries" => {
+                    options.retries = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|retries| *retries <= 10)
+                        .ok_or_else(|| invalid("a number from 0 to 10"))?
+                }
+                "retry_backoff" => {
+                    options.retry_backoff = parse_duration(&value)
+                        .ok_or_else(|| invalid("a duration like 500ms or 1s"))?
+                }
+                _ => return Err(format!("Unknown option `{key}`")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The redirect limit for libcurl, where a negative count disables
+    /// following.
+    pub fn curl_max_redirects(&self) -> i8 {
+        if self.follow_redirects {
+            self.max_redirects as i8
+        } else {
+            -1
+        }
+    }
+
+    /// Delay before retry number `attempt` (starting at 1).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+/// Methods that are safe to send twice.
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        method,
+        Method::Get | Method::Head | Method::Options | Method::Put | Method::Delete | Method::Trace
+    )
+}
+
+/// Parses `30s`, `500ms`, `2m` or a bare number of seconds.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(idx) => value.split_at(idx),
+        None => (value, "s"),
+    };
+    let number = number.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "s" => number,
+        "m" => number * 60.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Like [`parse_duration`], with `0`, `none` and `off` meaning no limit.
+fn parse_optional_duration(value: &str) -> Option<Option<Duration>> {
+    if matches!(value.to_ascii_lowercase().as_str(), "none" | "off") {
+        return Some(None);
+    }
+    parse_duration(value).map(|duration| (!duration.is_zero()).then_some(duration))
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Formats a duration the way the options panel accepts it.
+pub fn format_duration(duration: Duration) -> String {
+    if duration.subsec_millis() == 0 {
+        format!("{}s", duration.as_secs())
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::SendOptions;
+
+    #[test]
+    fn parses_options_panel() {
+        let options = SendOptions::parse(
+            "timeout: 30s\nconnect-timeout: 500ms\nfollow_redirects: off\nretries: 2\nretry_backoff: 1.5",
+        )
+        .unwrap();
+        assert_eq!(options.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.connect_timeout, Some(Duration::from_millis(500)));
+        assert_eq!(options.curl_max_redirects(), -1);
+        assert_eq!(options.retries, 2);
+        assert_eq!(options.backoff(3), Duration::from_secs(6));
+        assert_eq!(SendOptions::parse("").unwrap(), SendOptions::default());
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        assert_eq!(
+            SendOptions::parse("retry: 3"),
+            Err("Unknown option `retry`".to_string())
+        );
+        assert_eq!(
+            SendOptions::parse("timeout: soon"),
+            Err("`timeout` expects a duration like 30s or 500ms, got `soon`".to_string())
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use curl::easy::{Easy2, Handler, List, WriteError};
+use curl_rest::{Method, Response, ResponseHeader, StatusCode};
+
+use crate::types::{request::SendError, send_options::SendOptions};
+
+/// A request as libcurl sends it. `curl_rest::Client` keeps its handle to
+/// itself, so timeouts and cancellation are set on our own handle instead.
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub method: Method,
+    /// The full URL, query included.
+    pub url: String,
+    /// Every header to send; nothing is added by default.
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    /// Asks for `br` and decodes it here, since libcurl may be built
+    /// without brotli.
+    pub brotli: bool,
+    pub options: SendOptions,
+}
+
+/// A response and the URL it came from, after any redirects.
+#[derive(Debug, Clone)]
+pub struct Received {
+    pub response: Response,
+    pub url: String,
+    /// The redirects followed on the way, oldest first.
+    pub redirects: Vec<Redirect>,
+}
+
+/// A redirect response, kept for the cookies it set.
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    pub url: String,
+    pub headers: Vec<ResponseHeader>,
+}
+
+impl Transfer {
+    /// Runs the transfer on this thread, without following redirects.
+    /// Setting `cancelled` aborts it from libcurl's progress callback, which
+    /// runs at least once a second even while the connection is idle.
+    pub fn perform(&self, cancelled: &AtomicBool) -> Result<Received, SendError> {
+        let started = Instant::now();
+        let mut easy = Easy2::new(Collector::new(cancelled));
+        self.configure(&mut easy)?;
+        if let Err(err) = easy.perform() {
+            if err.is_aborted_by_callback() && cancelled.load(Ordering::Relaxed) {
+                return Err(SendError::Cancelled);
+            }
+            if !err.is_operation_timedout() {
+                return Err(SendError::from_curl(err.into()));
+            }
+            let connected = easy.connect_time().is_ok_and(|time| !time.is_zero());
+            return Err(match (self.options.connect_timeout, self.options.timeout) {
+                (Some(limit), total) if !connected && total.is_none_or(|total| limit <= total) => {
+                    SendError::ConnectTimeout(limit)
+                }
+                (_, total) => SendError::Timeout(total.unwrap_or_else(|| started.elapsed())),
+            });
+        }
+
+        let code = easy
+            .response_code()
+            .map_err(|err| SendError::from_curl(err.into()))?;
+        let status = u16::try_from(code)
+            .ok()
+            .and_then(StatusCode::from_u16)
+            .ok_or_else(|| SendError::from_curl(curl_rest::Error::InvalidStatusCode(code)))?;
+        let Collector { body, headers, .. } = easy.get_mut();
+        let body = std::mem::take(body);
+        let headers = std::mem::take(headers);
+        let body = if headers.iter().any(|header| {
+            header.name.eq_ignore_ascii_case("content-encoding")
+                && header.value.eq_ignore_ascii_case("br")
+        }) {
+            let mut decoded = Vec::new();
+            brotli_decompressor::BrotliDecompress(&mut body.as_slice(), &mut decoded)
+                .map_err(|err| SendError::from_curl(curl_rest::Error::BrotliDecompression(err)))?;
+            decoded
+        } else {
+            body
+        };
+        Ok(Received {
+            response: Response {
+                status,
+                headers,
+                body,
+            },
+            url: self.url.clone(),
+            redirects: Vec::new(),
+        })
+    }
+
+    fn configure(&self, easy: &mut Easy2<Collector<'_>>) -> Result<(), SendError> {
+        let curl = |err: curl::Error| SendError::from_curl(err.into());
+        match self.method {
+            Method::Get => easy.get(true),
+            Method::Post => easy.post(true),
+            Method::Head => easy.nobody(true),
+            Method::Put => easy.custom_request("PUT"),
+            Method::Delete => easy.custom_request("DELETE"),
+            Method::Options => easy.custom_request("OPTIONS"),
+            Method::Patch => easy.custom_request("PATCH"),
+            Method::Connect => easy.custom_request("CONNECT"),
+            Method::Trace => easy.custom_request("TRACE"),
+        }
+        .map_err(curl)?;
+        easy.progress(true).map_err(curl)?;
+        if let Some(timeout) = self.options.connect_timeout {
+            easy.connect_timeout(timeout).map_err(curl)?;
+        }
+        if let Some(timeout) = self.options.timeout {
+            easy.timeout(timeout).map_err(curl)?;
+        }
+
+        let mut list = List::new();
+        if self.brotli {
+            if !self
+                .headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("accept-encoding"))
+            {
+                list.append("Accept-Encoding: br").map_err(curl)?;
+            }
+        } else {
+            easy.accept_encoding("gzip").map_err(curl)?;
+        }
+        for (name, value) in &self.headers {
+            if name.is_empty() || !name.bytes().all(is_tchar) {
+                return Err(SendError::from_curl(curl_rest::Error::InvalidHeaderName(
+                    name.clone(),
+                )));
+            }
+            if value.contains(['\r', '\n']) {
+                return Err(SendError::from_curl(curl_rest::Error::InvalidHeaderValue(
+                    name.clone(),
+                )));
+            }
+            list.append(&format!("{name}: {value}")).map_err(curl)?;
+        }
+        easy.http_headers(list).map_err(curl)?;
+
+        if let Some(body) = &self.body {
+            easy.post_fields_copy(body).map_err(curl)?;
+        }
+        if url::Url::parse(&self.url).is_err() {
+            return Err(SendError::from_curl(curl_rest::Error::InvalidUrl(
+                self.url.clone(),
+            )));
+        }
+        easy.url(&self.url).map_err(curl)
+    }
+}
+
+/// Collects the body and the headers of the response, the way `curl_rest`
+/// does.
+#[derive(Debug)]
+struct Collector<'a> {
+    body: Vec<u8>,
+    headers: Vec<ResponseHeader>,
+    cancelled: &'a AtomicBool,
+}
+
+impl<'a> Collector<'a> {
+    fn new(cancelled: &'a AtomicBool) -> Self {
+        Self {
+            body: Vec::new(),
+            headers: Vec::new(),
+            cancelled,
+        }
+    }
+}
+
+impl Handler for Collector<'_> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
+        self.body.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn header(&mut self, data: &[u8]) -> bool {
+        let Ok(line) = std::str::from_utf8(data) else {
+            return true;
+        };
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() || line.starts_with("HTTP/") {
+            return true;
+        }
+        if line.starts_with([' ', '\t']) {
+            // A folded continuation of the previous header.
+            if let Some(last) = self.headers.last_mut() {
+                let trimmed = line.trim();
+                if !trimmed.is_empty() {
+                    if !last.value.is_empty() {
+                        last.value.push(' ');
+                    }
+                    last.value.push_str(trimmed);
+                }
+            }
+            return true;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim();
+            if !name.is_empty() {
+                self.headers.push(ResponseHeader {
+                    name: name.to_string(),
+                    value: value.trim().to_string(),
+                });
+            }
+        }
+        true
+    }
+
+    fn progress(&mut self, _: f64, _: f64, _: f64, _: f64) -> bool {
+        !self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// RFC 9110 token characters, the only ones allowed in a header name.
+fn is_tchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+            mpsc,
+        },
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    };
+
+    use curl_rest::Method;
+
+    use super::Transfer;
+    use crate::types::{request::SendError, send_options::SendOptions};
+
+    /// Accepts one request and sends part of a response, then waits for the
+    /// client to hang up.
+    fn stalled_server() -> (Transfer, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc");
+            while stream.read(&mut buf).is_ok_and(|read| read > 0) {}
+        });
+        let transfer = Transfer {
+            method: Method::Get,
+            url: format!("http://127.0.0.1:{port}/slow"),
+            headers: Vec::new(),
+            body: None,
+            brotli: false,
+            options: SendOptions::default(),
+        };
+        (transfer, server)
+    }
+
+    #[test]
+    fn total_timeout_stops_a_stalled_transfer() {
+        let (mut transfer, server) = stalled_server();
+        transfer.options.timeout = Some(Duration::from_millis(300));
+        let started = Instant::now();
+        assert_eq!(
+            transfer.perform(&AtomicBool::new(false)).unwrap_err(),
+            SendError::Timeout(Duration::from_millis(300))
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn cancelling_aborts_a_stalled_transfer() {
+        let (transfer, server) = stalled_server();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+        let worker = thread::spawn({
+            let cancelled = cancelled.clone();
+            move || {
+                let _ = tx.send(transfer.perform(&cancelled));
+            }
+        });
+        thread::sleep(Duration::from_millis(200));
+        cancelled.store(true, Ordering::Relaxed);
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(3))
+                .unwrap()
+                .unwrap_err(),
+            SendError::Cancelled
+        );
+        worker.join().unwrap();
+        // The server only returns once curl has closed the connection.
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+use serde_json_path::JsonPath;
+
+use crate::{
+    types::{app::App, binary::format_size},
+    ui::{clipboard::copy_to_clipboard, sections::response_body::parse_json_body},
+};
+
+/// Shown in the status bar while `y` waits for its target.
+const YANK_HINT: &str = "Yank: u URL • b body • h headers • s status • l line • Esc cancel";
+
+/// What the second key after `y` copies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YankTarget {
+    Url,
+    Body,
+    Headers,
+    Status,
+    /// The selected search match's line, or the top visible line.
+    Line,
+}
+
+impl YankTarget {
+    pub fn from_key(key: char) -> Option<Self> {
+        match key {
+            'u' => Some(Self::Url),
+            'b' => Some(Self::Body),
+            'h' => Some(Self::Headers),
+            's' => Some(Self::Status),
+            'l' => Some(Self::Line),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Url => "URL",
+            Self::Body => "response body",
+            Self::Headers => "response headers",
+            Self::Status => "status line",
+            Self::Line => "line",
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    /// Arms `y`; the next key picks what to copy.
+    pub(crate) fn start_yank(&mut self) {
+        self.app_state.yank_pending = true;
+        self.app_state.status_message = Some(YANK_HINT.to_string());
+    }
+
+    /// Handles the key after `y`. Any key that is not a target cancels.
+    pub(crate) fn finish_yank(&mut self, key: Option<char>) {
+        self.app_state.yank_pending = false;
+        match key.and_then(YankTarget::from_key) {
+            Some(target) => self.yank(target),
+            None => self.app_state.status_message = None,
+        }
+    }
+
+    pub(crate) fn yank(&mut self, target: YankTarget) {
+        if target == YankTarget::Body && self.app_state.response_binary {
+            self.app_state.error =
+                Some("Binary bodies cannot be copied; press w to save them".to_string());
+            return;
+        }
+        let mut label = target.label().to_string();
+        let text = match target {
+            YankTarget::Url => Some(self.yank_url()),
+            YankTarget::Body => self.response_display_text().map(str::to_string),
+            YankTarget::Headers => self.app_state.response_headers.clone(),
+            YankTarget::Status => self.app_state.response_status.clone(),
+            YankTarget::Line => self.current_line().map(|(index, line)| {
+                label = format!("line {}", index + 1);
+                line.to_string()
+            }),
+        };
+        match text {
+            Some(text) => self.copy_text(&label, &text),
+            None => self.app_state.status_message = Some(format!("No {label} to copy")),
+        }
+    }
+
+    /// Copies the JSON value under the tree cursor.
+    pub(crate) fn copy_tree_value(&mut self) {
+        let Some(path) = self.active_response_tree().and_then(|tree| {
+            tree.cursor_node()
+                .map(|index| tree.node(index).path.clone())
+        }) else {
+            return;
+        };
+        let value = self
+            .app_state
+            .response_body
+            .as_deref()
+            .and_then(|body| parse_json_body(body, self.app_state.response_content_type.as_deref()))
+            .and_then(|document| {
+                let path = JsonPath::parse(&path).ok()?;
+                path.query(&document).first().cloned()
+            });
+        let Some(value) = value else {
+            return;
+        };
+        let text = match &value {
+            serde_json::Value::String(text) => text.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        self.copy_text(&path, &text);
+    }
+
+    /// The request URL with variables resolved and the query appended; the
+    /// raw URL bar text when the request cannot be built.
+    fn yank_url(&self) -> String {
+        match self.prepared_request() {
+            Ok(prepared) => prepared.full_url(),
+            Err(_) => self.url_input.value().to_string(),
+        }
+    }
+
+    /// Index and text of the line `yl` copies.
+    fn current_line(&self) -> Option<(usize, &str)> {
+        let index = match self.app_state.search.current_match() {
+            Some(found) => found.line,
+            None => self.app_state.response_scroll as usize,
+        };
+        let line = self.response_display_text()?.lines().nth(index)?;
+        Some((index, line))
+    }
+
+    fn copy_text(&mut self, label: &str, text: &str) {
+        match copy_to_clipboard(text) {
+            Ok(()) => {
+                self.app_state.status_message = Some(format!(
+                    "Copied {label} ({}) to the clipboard",
+                    format_size(text.len())
+                ));
+            }
+            Err(e) => self.app_state.error = Some(format!("Failed to copy: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tui_input::Input;
+
+    use crate::types::app::App;
+
+    #[test]
+    fn current_line_prefers_the_selected_match() {
+        let mut app = App::default();
+        app.app_state.response_body = Some("first\nsecond\nneedle\nlast".to_string());
+        app.app_state.response_scroll = 1;
+        assert_eq!(app.current_line(), Some((1, "second")));
+
+        app.search_input = Input::new("needle".to_string());
+        app.refresh_search();
+        assert_eq!(app.current_line(), Some((2, "needle")));
+    }
+}
@@ -0,0 +1,39 @@
+use std::{
+    env,
+    io::{self, Write},
+};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+/// Copies `text` to the terminal's clipboard with an OSC 52 escape sequence,
+/// which also works over SSH. Inside tmux the sequence is wrapped in a DCS
+/// passthrough so it reaches the outer terminal.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let sequence = osc52_sequence(text, env::var_os("TMUX").is_some());
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::osc52_sequence;
+
+    #[test]
+    fn wraps_the_osc52_sequence_for_tmux() {
+        assert_eq!(osc52_sequence("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(
+            osc52_sequence("hi", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
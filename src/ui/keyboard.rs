@@ -1,8 +1,9 @@
 use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use tui_input::{InputRequest, backend::crossterm::EventHandler};
 
-use crate::types::app::{ActivePanel, Mode};
+use crate::types::app::{ActivePanel, Mode, Overlay};
 use crate::types::input_handler::InputHandler;
+use crate::types::response_file::SaveContent;
 
 impl<'b, 'a> InputHandler<'b, 'a> {
     pub fn handle(&mut self, key: KeyEvent) {
@@ -11,6 +12,38 @@ impl<'b, 'a> InputHandler<'b, 'a> {
             return;
         }
 
+        // Ctrl+C only ever cancels; it must not reach the `c` binding.
+        let ctrl_c =
+            key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        if ctrl_c || (self.app.app_state.is_loading && key.code == KeyCode::Esc) {
+            self.app.cancel_request();
+            return;
+        }
+
+        if self.app.prompt.is_some() {
+            self.state.key_code = key.code;
+            self.prompt_mode(key);
+            return;
+        }
+
+        if let Some(overlay) = self.app.app_state.overlay {
+            self.state.key_code = key.code;
+            self.overlay_mode(overlay, key);
+            return;
+        }
+
+        if self.app.app_state.search.editing {
+            self.state.key_code = key.code;
+            self.search_mode(key);
+            return;
+        }
+
+        if self.app.app_state.filter.editing {
+            self.state.key_code = key.code;
+            self.filter_mode(key);
+            return;
+        }
+
         match self.app.app_state.mode {
             Mode::Normal => {
                 self.state.key_code = key.code;
@@ -24,9 +57,29 @@ impl<'b, 'a> InputHandler<'b, 'a> {
     }
 
     fn normal_mode(&mut self) {
+        if self.app.app_state.yank_pending {
+            let key = match self.state.key_code {
+                KeyCode::Char(key) => Some(key),
+                _ => None,
+            };
+            self.app.finish_yank(key);
+            return;
+        }
+        if self.app.app_state.active_panel == ActivePanel::ResBody
+            && self.app.active_response_tree().is_some()
+            && self.tree_keys()
+        {
+            return;
+        }
         match self.state.key_code {
             KeyCode::Backspace => {}
-            KeyCode::Enter => self.app.send_request(),
+            KeyCode::Enter => {
+                if self.app.app_state.active_panel == ActivePanel::Collections {
+                    self.app.load_selected_collection_item();
+                } else {
+                    self.app.send_request();
+                }
+            }
             KeyCode::Left => {}
             KeyCode::Right => {}
             KeyCode::Up => {}
@@ -36,10 +89,10 @@ impl<'b, 'a> InputHandler<'b, 'a> {
             KeyCode::PageUp => {}
             KeyCode::PageDown => {}
             KeyCode::Tab => {
-                self.app.app_state.active_panel = self.app.app_state.active_panel.next();
+                self.app.focus_next_panel();
             }
             KeyCode::BackTab => {
-                self.app.app_state.active_panel = self.app.app_state.active_panel.prev();
+                self.app.focus_prev_panel();
             }
             KeyCode::Delete => {}
             KeyCode::Insert => {}
@@ -49,13 +102,18 @@ impl<'b, 'a> InputHandler<'b, 'a> {
                     self.app.app_state.active_panel,
                     ActivePanel::Url
                         | ActivePanel::ReqQuery
+                        | ActivePanel::ReqOptions
                         | ActivePanel::ReqHeaders
+                        | ActivePanel::ReqAuth
                         | ActivePanel::ReqBody
                 ) {
                     self.app.app_state.mode = Mode::Edit;
                 }
             }
             KeyCode::Char('j') => {
+                if self.app.app_state.active_panel == ActivePanel::Collections {
+                    self.app.select_next_collection_row();
+                }
                 if self.app.app_state.active_panel == ActivePanel::ResBody {
                     let max_scroll = self
                         .app
@@ -69,6 +127,9 @@ impl<'b, 'a> InputHandler<'b, 'a> {
                 }
             }
             KeyCode::Char('k') => {
+                if self.app.app_state.active_panel == ActivePanel::Collections {
+                    self.app.select_prev_collection_row();
+                }
                 if self.app.app_state.active_panel == ActivePanel::ResBody {
                     self.app.app_state.response_scroll =
                         self.app.app_state.response_scroll.saturating_sub(1);
@@ -105,12 +166,111 @@ impl<'b, 'a> InputHandler<'b, 'a> {
             KeyCode::Char('B') => {
                 self.app.prev_body_mode();
             }
+            KeyCode::Char('t') => {
+                if self.app.app_state.active_panel == ActivePanel::ReqBody {
+                    self.app.next_raw_type();
+                }
+            }
+            KeyCode::Char('T') => {
+                if self.app.app_state.active_panel == ActivePanel::ReqBody {
+                    self.app.prev_raw_type();
+                }
+            }
+            KeyCode::Char('p') => {
+                if self.app.app_state.active_panel == ActivePanel::ReqBody {
+                    self.app.format_raw_body();
+                }
+            }
+            KeyCode::Char('H') => {
+                self.app.open_history();
+            }
+            KeyCode::Char('K') => {
+                self.app.open_cookies();
+            }
+            KeyCode::Char('c') => {
+                self.app.toggle_collections();
+            }
+            KeyCode::Char('I') => {
+                self.app.prompt_import_curl();
+            }
+            KeyCode::Char('x') => {
+                self.app.open_export();
+            }
+            KeyCode::Char('e') => {
+                self.app.next_environment();
+            }
+            KeyCode::Char('E') => {
+                self.app.prev_environment();
+            }
+            KeyCode::Char('s') => {
+                self.app.prompt_save_request();
+            }
+            KeyCode::Char('S') => {
+                self.app.save_request();
+            }
+            KeyCode::Char('r') => {
+                if self.app.app_state.active_panel == ActivePanel::Collections {
+                    self.app.prompt_rename_collection_item();
+                }
+            }
+            KeyCode::Char('d') => {
+                if self.app.app_state.active_panel == ActivePanel::Collections {
+                    self.app.prompt_delete_collection_item();
+                }
+            }
+            KeyCode::Char('D') => {
+                if self.app.app_state.active_panel == ActivePanel::Collections {
+                    self.app.duplicate_collection_item();
+                }
+            }
+            KeyCode::Char('/') => {
+                self.app.start_search();
+            }
+            KeyCode::Char('f') => {
+                self.app.start_filter();
+            }
+            KeyCode::Char('w') => {
+                self.app.prompt_save_response(SaveContent::Raw);
+            }
+            KeyCode::Char('W') => {
+                self.app.prompt_save_response(SaveContent::Formatted);
+            }
+            KeyCode::Char('y') => {
+                self.app.start_yank();
+            }
+            KeyCode::Char('C') => {
+                self.app.prompt_response_charset();
+            }
+            KeyCode::Char('v') => {
+                if self.app.app_state.active_panel == ActivePanel::ResBody {
+                    self.app.toggle_response_tree();
+                }
+            }
+            KeyCode::Char('F') => {
+                if self.app.app_state.active_panel == ActivePanel::ResBody {
+                    self.app.toggle_filter();
+                }
+            }
+            KeyCode::Char('n') => {
+                if self.app.app_state.active_panel == ActivePanel::ResBody {
+                    self.app.next_match();
+                }
+            }
+            KeyCode::Char('N') => {
+                if self.app.app_state.active_panel == ActivePanel::ResBody {
+                    self.app.prev_match();
+                }
+            }
             KeyCode::Char('q') => {
                 self.app.app_state.should_exit = true;
             }
             KeyCode::Char(_) => {}
             KeyCode::Null => {}
-            KeyCode::Esc => {}
+            KeyCode::Esc => {
+                if self.app.app_state.active_panel == ActivePanel::ResBody {
+                    self.app.clear_search();
+                }
+            }
             KeyCode::CapsLock => {}
             KeyCode::ScrollLock => {}
             KeyCode::NumLock => {}
@@ -123,12 +283,44 @@ impl<'b, 'a> InputHandler<'b, 'a> {
         }
     }
 
+    /// Tree view navigation; returns `false` for keys it leaves to the
+    /// normal mode.
+    fn tree_keys(&mut self) -> bool {
+        match self.state.key_code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.app.update_response_tree(|tree| tree.move_cursor(1))
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.app.update_response_tree(|tree| tree.move_cursor(-1))
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.app.update_response_tree(|tree| tree.expand())
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.app.update_response_tree(|tree| tree.collapse())
+            }
+            KeyCode::Char(' ') => self.app.update_response_tree(|tree| tree.toggle()),
+            KeyCode::Char('*') => self.app.update_response_tree(|tree| tree.expand_all()),
+            KeyCode::Char(digit @ '0'..='9') => {
+                let depth = digit as usize - '0' as usize;
+                self.app
+                    .update_response_tree(|tree| tree.collapse_to_depth(depth));
+            }
+            KeyCode::Char('y') => self.app.copy_tree_path(),
+            KeyCode::Char('Y') => self.app.copy_tree_value(),
+            _ => return false,
+        }
+        true
+    }
+
     fn edit_mode(&mut self, key: KeyEvent) {
         let active_panel = self.app.app_state.active_panel;
         let active_input = match active_panel {
             ActivePanel::Url => Some(&mut self.app.url_input),
             ActivePanel::ReqQuery => Some(&mut self.app.req_query_input),
+            ActivePanel::ReqOptions => Some(&mut self.app.req_options_input),
             ActivePanel::ReqHeaders => Some(&mut self.app.req_headers_input),
+            ActivePanel::ReqAuth => Some(&mut self.app.req_auth_input),
             ActivePanel::ReqBody => Some(&mut self.app.req_body_input),
             _ => None,
         };
@@ -176,4 +368,100 @@ impl<'b, 'a> InputHandler<'b, 'a> {
             KeyCode::Modifier(_modifier_key_code) => {}
         }
     }
+
+    fn search_mode(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match self.state.key_code {
+            KeyCode::Esc => self.app.clear_search(),
+            KeyCode::Enter => self.app.finish_search(),
+            KeyCode::Char('r') if ctrl => self.app.toggle_search_regex(),
+            KeyCode::Char('t') if ctrl => self.app.toggle_search_case(),
+            _ => {
+                self.app.search_input.handle_event(&Event::Key(key));
+                self.app.update_search();
+            }
+        }
+    }
+
+    fn filter_mode(&mut self, key: KeyEvent) {
+        match self.state.key_code {
+            KeyCode::Esc => self.app.clear_filter(),
+            KeyCode::Enter => self.app.finish_filter(),
+            _ => {
+                self.app.filter_input.handle_event(&Event::Key(key));
+                self.app.update_filter();
+            }
+        }
+    }
+
+    fn prompt_mode(&mut self, key: KeyEvent) {
+        match self.state.key_code {
+            KeyCode::Esc => self.app.prompt = None,
+            KeyCode::Enter => self.app.submit_prompt(),
+            _ => {
+                if let Some(prompt) = self.app.prompt.as_mut() {
+                    prompt.input.handle_event(&Event::Key(key));
+                }
+            }
+        }
+    }
+
+    fn overlay_mode(&mut self, overlay: Overlay, key: KeyEvent) {
+        match overlay {
+            Overlay::History => self.history_overlay(key),
+            Overlay::Export => self.export_overlay(),
+            Overlay::Cookies => self.cookies_overlay(),
+        }
+    }
+
+    fn cookies_overlay(&mut self) {
+        match self.state.key_code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('K') => self.app.close_overlay(),
+            KeyCode::Char('j') | KeyCode::Down => self.app.select_next_cookie(),
+            KeyCode::Char('k') | KeyCode::Up => self.app.select_prev_cookie(),
+            KeyCode::Enter | KeyCode::Char('e') => self.app.prompt_edit_cookie(),
+            KeyCode::Char('d') | KeyCode::Delete => self.app.delete_selected_cookie(),
+            KeyCode::Char('D') => self.app.prompt_clear_cookies(),
+            _ => {}
+        }
+    }
+
+    fn export_overlay(&mut self) {
+        let format = self.app.app_state.export_format;
+        match self.state.key_code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('x') => self.app.close_overlay(),
+            KeyCode::Char('j') | KeyCode::Char('l') | KeyCode::Down | KeyCode::Tab => {
+                self.app.app_state.export_format = format.next();
+            }
+            KeyCode::Char('k') | KeyCode::Char('h') | KeyCode::Up | KeyCode::BackTab => {
+                self.app.app_state.export_format = format.prev();
+            }
+            KeyCode::Enter | KeyCode::Char('y') => self.app.copy_export_snippet(),
+            _ => {}
+        }
+    }
+
+    fn history_overlay(&mut self, key: KeyEvent) {
+        if self.app.app_state.history_filter_editing {
+            match self.state.key_code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    self.app.app_state.history_filter_editing = false;
+                }
+                _ => {
+                    self.app.history_filter_input.handle_event(&Event::Key(key));
+                    self.app.app_state.history_selected = 0;
+                }
+            }
+            return;
+        }
+
+        match self.state.key_code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('H') => self.app.close_overlay(),
+            KeyCode::Char('j') | KeyCode::Down => self.app.select_next_history(),
+            KeyCode::Char('k') | KeyCode::Up => self.app.select_prev_history(),
+            KeyCode::Char('/') => self.app.app_state.history_filter_editing = true,
+            KeyCode::Enter => self.app.load_selected_history(),
+            _ => {}
+        }
+    }
 }
@@ -5,8 +5,12 @@ pub struct MainLayout {
     pub method: Rect,
     pub url: Rect,
 
+    pub collections: Rect,
+
     pub req_query: Rect,
+    pub req_options: Rect,
     pub req_headers: Rect,
+    pub req_auth: Rect,
     pub req_body: Rect,
 
     pub res_headers: Rect,
@@ -18,9 +22,9 @@ pub struct MainLayout {
 impl MainLayout {
     /// Split the full screen into:
     /// - Top bar (method + url)
-    /// - Main content split (request left, response right)
+    /// - Main content split (optional collections sidebar, request left, response right)
     /// - Bottom status bar
-    pub fn split(area: Rect) -> Self {
+    pub fn split(area: Rect, show_collections: bool) -> Self {
         // Whole screen: header, content, status
         let [header, content, status] = Layout::default()
             .direction(Direction::Vertical)
@@ -46,6 +50,16 @@ impl MainLayout {
             .try_into()
             .expect("header split must yield 2 rects");
 
+        // Content: collections sidebar (when shown) + the rest
+        let sidebar_width = if show_collections { 28 } else { 0 };
+        let [collections, content] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(sidebar_width), Constraint::Min(1)])
+            .split(content)
+            .as_ref()
+            .try_into()
+            .expect("sidebar split must yield 2 rects");
+
         // Content: request left + response right
         let [request, response] = Layout::default()
             .direction(Direction::Horizontal)
@@ -55,8 +69,8 @@ impl MainLayout {
             .try_into()
             .expect("content split must yield 2 rects");
 
-        // Request pane: query, headers, body (stacked)
-        let [req_query, req_headers, req_body] = Layout::default()
+        // Request pane: query + options, headers + auth, body (stacked)
+        let [req_top, req_middle, req_body] = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(33),
@@ -68,6 +82,22 @@ impl MainLayout {
             .try_into()
             .expect("request split must yield 3 rects");
 
+        let [req_query, req_options] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(req_top)
+            .as_ref()
+            .try_into()
+            .expect("query/options split must yield 2 rects");
+
+        let [req_headers, req_auth] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(req_middle)
+            .as_ref()
+            .try_into()
+            .expect("headers/auth split must yield 2 rects");
+
         // Response pane: headers + body
         let [res_headers, res_body] = Layout::default()
             .direction(Direction::Vertical)
@@ -80,8 +110,11 @@ impl MainLayout {
         Self {
             method,
             url,
+            collections,
             req_query,
+            req_options,
             req_headers,
+            req_auth,
             req_body,
             res_headers,
             res_body,
@@ -1,54 +1,77 @@
+pub mod clipboard;
 pub mod keyboard;
 pub mod layout;
 pub mod sections;
 
-use std::{env, time::Duration};
+use std::{io, time::Duration};
 
 use ratatui::{
     DefaultTerminal, Frame,
-    crossterm::event,
+    crossterm::{
+        event::{self, DisableBracketedPaste, EnableBracketedPaste},
+        execute,
+    },
     layout::Rect,
     style::{Color, Style},
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
 };
 
 use crate::ui::sections::{
+    collections::{CollectionsSidebar, CollectionsSidebarProps},
+    cookies::{CookiesOverlay, CookiesOverlayProps},
+    export::{ExportOverlay, ExportOverlayProps},
+    history::{HistoryOverlay, HistoryOverlayProps},
     method::{Method, MethodProps},
+    prompt::{PromptBox, PromptBoxProps},
     query_params::{QueryParams, QueryParamsProps},
+    request_auth::{RequestAuth, RequestAuthProps},
     request_body::{RequestBody, RequestBodyProps},
     request_headers::{RequestHeaders, RequestHeadersProps},
-    response_body::ResponseBody,
+    request_options::{RequestOptions, RequestOptionsProps},
+    response_body::{ResponseBody, ResponseBodyProps},
     response_headers::{ResponseHeaders, ResponseHeadersProps},
     status_bar::{StatusBar, StatusBarProps},
     url_bar::UrlBar,
 };
 use crate::{
-    types::app::{ActivePanel, App, Mode},
+    cli::args::RequestArgs,
+    types::{
+        app::{ActivePanel, App, BodyMode, Mode, Overlay},
+        collection::Collections,
+        cookies::CookieJar,
+        environment::Environments,
+        history::{History, unix_timestamp},
+        request::RequestSpec,
+    },
     ui::layout::MainLayout,
 };
 
 impl<'a> App<'a> {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) {
+        let _ = execute!(io::stdout(), EnableBracketedPaste);
         loop {
             terminal.draw(|frame| self.draw(frame)).unwrap();
 
             if event::poll(Duration::from_millis(50)).unwrap() {
-                let event = event::read().unwrap();
-                if let event::Event::Key(key_event) = event {
-                    self.handle_key_events(key_event)
+                match event::read().unwrap() {
+                    event::Event::Key(key_event) => self.handle_key_events(key_event),
+                    event::Event::Paste(text) => self.handle_paste(&text),
+                    _ => {}
                 }
             }
 
             self.poll_network();
+            self.poll_filter();
 
             if self.app_state.should_exit {
                 break;
             }
         }
+        let _ = execute!(io::stdout(), DisableBracketedPaste);
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let l = MainLayout::split(frame.area());
+        let l = MainLayout::split(frame.area(), self.app_state.show_collections);
         let active_panel = self.app_state.active_panel;
 
         // Method box
@@ -65,6 +88,21 @@ impl<'a> App<'a> {
         let url_bar = UrlBar(self);
         url_bar.render(frame, l.url);
 
+        // Collections sidebar
+        if self.app_state.show_collections {
+            CollectionsSidebar.render(
+                frame,
+                CollectionsSidebarProps {
+                    area: l.collections,
+                    active: active_panel == ActivePanel::Collections,
+                    rows: self.collections.rows(),
+                    items: self.collections.items(),
+                    selected: self.app_state.collection_selected,
+                    loaded: self.loaded_collection_item.as_ref(),
+                },
+            );
+        }
+
         // Request sections (left)
         QueryParams.render(
             frame,
@@ -75,6 +113,21 @@ impl<'a> App<'a> {
                 cursor: self.req_query_input.cursor(),
                 show_cursor: self.app_state.mode == Mode::Edit
                     && active_panel == ActivePanel::ReqQuery,
+                variables: self.environments.active_variables(),
+            },
+        );
+
+        RequestOptions.render(
+            frame,
+            RequestOptionsProps {
+                area: l.req_options,
+                active: active_panel == ActivePanel::ReqOptions,
+                value: self.req_options_input.value(),
+                cursor: self.req_options_input.cursor(),
+                show_cursor: self.app_state.mode == Mode::Edit
+                    && active_panel == ActivePanel::ReqOptions,
+                variables: self.environments.active_variables(),
+                error: self.options_validation(),
             },
         );
 
@@ -87,6 +140,22 @@ impl<'a> App<'a> {
                 cursor: self.req_headers_input.cursor(),
                 show_cursor: self.app_state.mode == Mode::Edit
                     && active_panel == ActivePanel::ReqHeaders,
+                variables: self.environments.active_variables(),
+            },
+        );
+
+        RequestAuth.render(
+            frame,
+            RequestAuthProps {
+                area: l.req_auth,
+                active: active_panel == ActivePanel::ReqAuth,
+                value: self.req_auth_input.value(),
+                cursor: self.req_auth_input.cursor(),
+                show_cursor: self.app_state.mode == Mode::Edit
+                    && active_panel == ActivePanel::ReqAuth,
+                variables: self.environments.active_variables(),
+                error: self.auth_validation(),
+                status: self.auth_status(),
             },
         );
 
@@ -99,17 +168,23 @@ impl<'a> App<'a> {
                 cursor: self.req_body_input.cursor(),
                 show_cursor: self.app_state.mode == Mode::Edit
                     && active_panel == ActivePanel::ReqBody,
-                content_type: self.body_content_type(),
+                variables: self.environments.active_variables(),
+                content_type: &self.body_content_type(),
+                raw: self.app_state.body_mode == BodyMode::Raw,
+                validation: self.body_validation(),
+                file_notes: self.multipart_file_notes(),
             },
         );
 
         // Response sections (right)
         self.app_state.response_viewport_height = l.res_body.height.saturating_sub(2);
         self.app_state.response_viewport_width = l.res_body.width.saturating_sub(2);
-        let max_scroll = self
-            .app_state
-            .response_line_count
-            .saturating_sub(self.app_state.response_viewport_height as usize);
+        let line_count = match self.active_response_tree() {
+            Some(tree) => tree.rows().len(),
+            None => self.app_state.response_line_count,
+        };
+        let max_scroll =
+            line_count.saturating_sub(self.app_state.response_viewport_height as usize);
         let max_scroll = (max_scroll.min(u16::MAX as usize)) as u16;
         if self.app_state.response_scroll > max_scroll {
             self.app_state.response_scroll = max_scroll;
@@ -125,13 +200,22 @@ impl<'a> App<'a> {
 
         ResponseBody.render(
             frame,
-            l.res_body,
-            active_panel == ActivePanel::ResBody,
-            self.app_state.response_body.as_deref(),
-            self.app_state.response_formatted_body.as_deref(),
-            self.app_state.response_content_type.as_deref(),
-            self.app_state.response_scroll,
-            self.app_state.response_scroll_x,
+            ResponseBodyProps {
+                area: l.res_body,
+                active: active_panel == ActivePanel::ResBody,
+                body: self.app_state.response_body.as_deref(),
+                formatted_body: self.app_state.response_formatted_body.as_deref(),
+                content_type: self.app_state.response_content_type.as_deref(),
+                scroll: self.app_state.response_scroll,
+                scroll_x: self.app_state.response_scroll_x,
+                search: &self.app_state.search,
+                search_query: self.search_input.value(),
+                search_cursor: self.search_input.visual_cursor(),
+                filter: &self.app_state.filter,
+                filter_query: self.filter_input.value(),
+                filter_cursor: self.filter_input.visual_cursor(),
+                tree: self.active_response_tree(),
+            },
         );
 
         ResponseHeaders.render(
@@ -142,6 +226,10 @@ impl<'a> App<'a> {
                 status: self.app_state.response_status.as_deref(),
                 headers: self.app_state.response_headers.as_deref(),
                 response_time: self.app_state.response_time,
+                charset: self
+                    .app_state
+                    .response_charset
+                    .map(|charset| charset.label()),
             },
         );
 
@@ -154,20 +242,87 @@ impl<'a> App<'a> {
                 active_panel,
                 is_loading: self.app_state.is_loading,
                 error: self.app_state.error.as_deref(),
+                message: self.app_state.status_message.as_deref(),
+                search: self.search_status().as_deref(),
             },
         );
 
+        if self.app_state.overlay == Some(Overlay::History) {
+            let entries = self
+                .visible_history()
+                .into_iter()
+                .filter_map(|idx| self.history.entries().get(idx))
+                .collect();
+            HistoryOverlay.render(
+                frame,
+                HistoryOverlayProps {
+                    area: centered_area(frame.area(), 100, 24),
+                    entries,
+                    selected: self.app_state.history_selected,
+                    filter: self.history_filter_input.value(),
+                    filter_cursor: self.history_filter_input.visual_cursor(),
+                    filter_editing: self.app_state.history_filter_editing,
+                },
+            );
+        }
+
+        if self.app_state.overlay == Some(Overlay::Cookies) {
+            CookiesOverlay.render(
+                frame,
+                CookiesOverlayProps {
+                    area: centered_area(frame.area(), 120, 24),
+                    cookies: self.cookies.cookies(),
+                    selected: self.app_state.cookie_selected,
+                    now: unix_timestamp(),
+                },
+            );
+        }
+
+        if self.app_state.overlay == Some(Overlay::Export) {
+            let snippet = self.export_snippet();
+            ExportOverlay.render(
+                frame,
+                ExportOverlayProps {
+                    area: centered_area(frame.area(), 100, 24),
+                    format: self.app_state.export_format,
+                    snippet: snippet.as_deref().map_err(String::as_str),
+                },
+            );
+        }
+
+        if let Some(prompt) = &self.prompt {
+            let area = frame.area();
+            let width = 70.min(area.width.saturating_sub(2));
+            let prompt_area = Rect {
+                x: area.x + (area.width.saturating_sub(width)) / 2,
+                y: area.y + area.height.saturating_sub(6),
+                width,
+                height: 3,
+            };
+            PromptBox.render(
+                frame,
+                PromptBoxProps {
+                    area: prompt_area,
+                    title: &prompt.kind.title(),
+                    value: prompt.input.value(),
+                    cursor: prompt.input.visual_cursor(),
+                },
+            );
+        }
+
         if self.app_state.is_loading {
             let loading_area = centered_area(frame.area(), 42, 7);
             frame.render_widget(Clear, loading_area);
-            let loading_indicator = Paragraph::new("Please wait for the request to complete.")
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_type(BorderType::Rounded)
-                        .title("Making request"),
-                )
-                .wrap(Wrap { trim: false });
+            let loading_indicator = Paragraph::new(
+                "Please wait for the request to complete.\nEsc or Ctrl+C to cancel.",
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Making request"),
+            )
+            .wrap(Wrap { trim: false });
             frame.render_widget(loading_indicator, loading_area);
         }
 
@@ -188,14 +343,52 @@ impl<'a> App<'a> {
     }
 }
 
-pub fn run() {
-    let mut application = App::default();
+/// Command-line setup applied before the terminal starts.
+#[derive(Debug, Default)]
+pub struct Launch {
+    pub request: RequestArgs,
+    pub from_curl: Option<String>,
+}
+
+pub fn run(launch: Launch) -> Result<(), String> {
+    let mut application = App::default()
+        .with_history(History::load())
+        .with_collections(Collections::load());
+
+    match CookieJar::load() {
+        Ok(cookies) => application = application.with_cookies(cookies),
+        Err(message) => application.app_state.error = Some(message),
+    }
 
-    if let Some(url) = env::args().nth(1) {
-        application = application.with_default_url(&url);
+    match Environments::load() {
+        Ok(mut environments) => {
+            if let Some(name) = &launch.request.env {
+                environments.activate(name)?;
+            }
+            application = application.with_environments(environments);
+        }
+        Err(message) if launch.request.env.is_some() => return Err(message),
+        Err(message) => application.app_state.error = Some(message),
+    }
+
+    if let Some(command) = &launch.from_curl {
+        application.import_curl(command)?;
+    }
+    if launch.request.target.is_some() || launch.request.edits_request() {
+        let mut spec = if launch.from_curl.is_some() {
+            application.request_spec()
+        } else {
+            RequestSpec::default()
+        };
+        if let Some(url) = &launch.request.target {
+            spec.url = url.clone();
+        }
+        launch.request.apply(&mut spec);
+        application.apply_request_spec(spec);
     }
 
-    ratatui::run(|terminal| application.run(terminal))
+    ratatui::run(|terminal| application.run(terminal));
+    Ok(())
 }
 
 fn centered_area(area: Rect, width: u16, height: u16) -> Rect {
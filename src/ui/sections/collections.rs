@@ -0,0 +1,94 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::types::collection::{CollectionItem, CollectionRow};
+
+pub struct CollectionsSidebar;
+
+pub struct CollectionsSidebarProps<'a> {
+    pub area: Rect,
+    pub active: bool,
+    pub rows: &'a [CollectionRow],
+    pub items: &'a [CollectionItem],
+    pub selected: usize,
+    pub loaded: Option<&'a CollectionItem>,
+}
+
+impl CollectionsSidebar {
+    pub fn render(&self, frame: &mut Frame, props: CollectionsSidebarProps<'_>) {
+        let CollectionsSidebarProps {
+            area,
+            active,
+            rows,
+            items,
+            selected,
+            loaded,
+        } = props;
+        if area.width == 0 {
+            return;
+        }
+        let title = if active {
+            "● Collections"
+        } else {
+            "○ Collections"
+        };
+        let border_style = if active {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+
+        if rows.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No saved requests\n\ns: Save current")
+                    .style(Style::default().fg(Color::DarkGray))
+                    .block(block),
+                area,
+            );
+            return;
+        }
+
+        let list_items = rows
+            .iter()
+            .map(|row| match row {
+                CollectionRow::Folder { name, depth } => ListItem::new(Line::from(Span::styled(
+                    format!("{}▾ {name}/", "  ".repeat(*depth)),
+                    Style::default().fg(Color::Blue),
+                ))),
+                CollectionRow::Request { item, depth } => {
+                    let item = &items[*item];
+                    let style = if loaded == Some(item) {
+                        Style::default().fg(Color::Green)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(Line::from(Span::styled(
+                        format!("{}{}", "  ".repeat(*depth), item.name),
+                        style,
+                    )))
+                }
+            })
+            .collect::<Vec<_>>();
+        let highlight_style = if active {
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let list = List::new(list_items)
+            .block(block)
+            .highlight_style(highlight_style);
+        let mut state = ListState::default().with_selected(Some(selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
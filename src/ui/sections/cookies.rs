@@ -0,0 +1,130 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::types::cookies::{Cookie, format_expiry};
+
+pub struct CookiesOverlay;
+
+pub struct CookiesOverlayProps<'a> {
+    pub area: Rect,
+    pub cookies: &'a [Cookie],
+    pub selected: usize,
+    /// Unix time, for showing when cookies expire.
+    pub now: u64,
+}
+
+impl CookiesOverlay {
+    pub fn render(&self, frame: &mut Frame, props: CookiesOverlayProps<'_>) {
+        let CookiesOverlayProps {
+            area,
+            cookies,
+            selected,
+            now,
+        } = props;
+
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("Cookies ({})", cookies.len()))
+            .title_bottom("j/k: Move • Enter: Edit value • d: Delete • D: Delete all • Esc: Close");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if cookies.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No cookies stored yet").style(Style::default().fg(Color::DarkGray)),
+                inner,
+            );
+            return;
+        }
+
+        let [list_area, detail_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(4)])
+            .split(inner)
+            .as_ref()
+            .try_into()
+            .expect("cookies split must yield 2 rects");
+
+        let domain_width = cookies
+            .iter()
+            .map(|cookie| display_domain(cookie).len())
+            .max()
+            .unwrap_or_default()
+            .min(32);
+        let items = cookies
+            .iter()
+            .map(|cookie| ListItem::new(cookie_line(cookie, domain_width, now)))
+            .collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+        let mut state = ListState::default().with_selected(Some(selected));
+        frame.render_stateful_widget(list, list_area, &mut state);
+
+        if let Some(cookie) = cookies.get(selected) {
+            let detail = Paragraph::new(vec![
+                Line::styled(
+                    "─".repeat(detail_area.width as usize),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Line::from(vec![
+                    Span::styled(
+                        format!("{}=", cookie.name),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(cookie.value.clone()),
+                ]),
+            ])
+            .wrap(Wrap { trim: false });
+            frame.render_widget(detail, detail_area);
+        }
+    }
+}
+
+/// `.example.com` for cookies shared with subdomains.
+fn display_domain(cookie: &Cookie) -> String {
+    if cookie.host_only {
+        cookie.domain.clone()
+    } else {
+        format!(".{}", cookie.domain)
+    }
+}
+
+fn cookie_line(cookie: &Cookie, domain_width: usize, now: u64) -> Line<'static> {
+    let mut flags = Vec::new();
+    if cookie.secure {
+        flags.push("Secure");
+    }
+    if cookie.http_only {
+        flags.push("HttpOnly");
+    }
+    Line::from(vec![
+        Span::styled(
+            format!("{:<domain_width$} ", display_domain(cookie)),
+            Style::default().fg(Color::Magenta),
+        ),
+        Span::styled(
+            format!("{:<12} ", cookie.path),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::styled(
+            format!("{:<12} ", format_expiry(cookie, now)),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            format!("{:<16} ", flags.join(" ")),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::raw(format!("{}={}", cookie.name, cookie.value)),
+    ])
+}
@@ -0,0 +1,66 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::types::export::ExportFormat;
+
+pub struct ExportOverlay;
+
+pub struct ExportOverlayProps<'a> {
+    pub area: Rect,
+    pub format: ExportFormat,
+    pub snippet: Result<&'a str, &'a str>,
+}
+
+impl ExportOverlay {
+    pub fn render(&self, frame: &mut Frame, props: ExportOverlayProps<'_>) {
+        let ExportOverlayProps {
+            area,
+            format,
+            snippet,
+        } = props;
+
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title("Export request")
+            .title_bottom("j/k: Format • Enter/y: Copy • Esc: Close");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [tabs_area, snippet_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(1)])
+            .split(inner)
+            .as_ref()
+            .try_into()
+            .expect("export split must yield 2 rects");
+
+        let mut tabs = Vec::new();
+        for candidate in ExportFormat::ALL {
+            let style = if candidate == format {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            tabs.push(Span::styled(format!(" {} ", candidate.label()), style));
+            tabs.push(Span::raw(" "));
+        }
+        frame.render_widget(Paragraph::new(Line::from(tabs)), tabs_area);
+
+        let preview = match snippet {
+            Ok(snippet) => Paragraph::new(snippet),
+            Err(message) => Paragraph::new(message).style(Style::default().fg(Color::Red)),
+        };
+        frame.render_widget(preview, snippet_area);
+    }
+}
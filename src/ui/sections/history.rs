@@ -0,0 +1,133 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::types::history::HistoryEntry;
+
+pub struct HistoryOverlay;
+
+pub struct HistoryOverlayProps<'a> {
+    pub area: Rect,
+    pub entries: Vec<&'a HistoryEntry>,
+    pub selected: usize,
+    pub filter: &'a str,
+    pub filter_cursor: usize,
+    pub filter_editing: bool,
+}
+
+impl HistoryOverlay {
+    pub fn render(&self, frame: &mut Frame, props: HistoryOverlayProps<'_>) {
+        let HistoryOverlayProps {
+            area,
+            entries,
+            selected,
+            filter,
+            filter_cursor,
+            filter_editing,
+        } = props;
+
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("History ({})", entries.len()))
+            .title_bottom("j/k: Move • /: Filter • Enter: Load • Esc: Close");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [filter_area, list_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(inner)
+            .as_ref()
+            .try_into()
+            .expect("history split must yield 2 rects");
+
+        let filter_style = if filter_editing {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Filter: ", filter_style),
+                Span::raw(filter),
+            ])),
+            filter_area,
+        );
+        if filter_editing {
+            let col = ("Filter: ".len() + filter_cursor) as u16;
+            let col = col.min(filter_area.width.saturating_sub(1));
+            frame.set_cursor_position((filter_area.x + col, filter_area.y));
+        }
+
+        if entries.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No requests recorded yet")
+                    .style(Style::default().fg(Color::DarkGray)),
+                list_area,
+            );
+            return;
+        }
+
+        let items = entries
+            .iter()
+            .map(|entry| ListItem::new(entry_line(entry)))
+            .collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+        let mut state = ListState::default().with_selected(Some(selected));
+        frame.render_stateful_widget(list, list_area, &mut state);
+    }
+}
+
+fn entry_line(entry: &HistoryEntry) -> Line<'static> {
+    let (status, status_color) = match (entry.status, &entry.error) {
+        (Some(status), _) => (status.to_string(), status_color(status)),
+        (None, Some(_)) => ("ERR".to_string(), Color::Red),
+        (None, None) => ("---".to_string(), Color::DarkGray),
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("{:<7} ", entry.request.method),
+            Style::default().fg(Color::Magenta),
+        ),
+        Span::styled(format!("{status:<4} "), Style::default().fg(status_color)),
+        Span::styled(
+            format!(
+                "{:>6}ms {:>8} ",
+                entry.elapsed_ms,
+                format_size(entry.response_size)
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::raw(entry.request.url.clone()),
+    ])
+}
+
+fn status_color(status: u16) -> Color {
+    match status {
+        200..=299 => Color::Green,
+        300..=399 => Color::Cyan,
+        400..=499 => Color::Rgb(255, 191, 0),
+        _ => Color::Red,
+    }
+}
+
+pub(crate) fn format_size(size: usize) -> String {
+    if size < 1024 {
+        format!("{size}B")
+    } else if size < 1024 * 1024 {
+        format!("{:.1}KB", size as f64 / 1024.0)
+    } else {
+        format!("{:.1}MB", size as f64 / (1024.0 * 1024.0))
+    }
+}
@@ -1,8 +1,16 @@
+pub mod collections;
+pub mod cookies;
+pub mod export;
+pub mod history;
 pub mod method;
+pub mod prompt;
 pub mod query_params;
+pub mod request_auth;
 pub mod request_body;
 pub mod request_headers;
+pub mod request_options;
 pub mod response_body;
 pub mod response_headers;
 pub mod status_bar;
 pub mod url_bar;
+pub mod variables;
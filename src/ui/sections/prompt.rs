@@ -0,0 +1,43 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+pub struct PromptBox;
+
+pub struct PromptBoxProps<'a> {
+    pub area: Rect,
+    pub title: &'a str,
+    pub value: &'a str,
+    pub cursor: usize,
+}
+
+impl PromptBox {
+    pub fn render(&self, frame: &mut Frame, props: PromptBoxProps<'_>) {
+        let PromptBoxProps {
+            area,
+            title,
+            value,
+            cursor,
+        } = props;
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let scroll = cursor.saturating_sub(inner_width.saturating_sub(1));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(value).scroll((0, scroll as u16)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(title)
+                    .title_bottom("Enter: Confirm • Esc: Cancel"),
+            ),
+            area,
+        );
+        let col = (cursor - scroll) as u16;
+        frame.set_cursor_position((area.x + col + 1, area.y + 1));
+    }
+}
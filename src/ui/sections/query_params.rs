@@ -2,9 +2,12 @@ use ratatui::{
     Frame,
     layout::Rect,
     style::{Color, Style},
+    text::Text,
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
+use crate::{types::environment::Variables, ui::sections::variables::highlight_placeholders};
+
 pub struct QueryParams;
 
 pub struct QueryParamsProps<'a> {
@@ -13,6 +16,7 @@ pub struct QueryParamsProps<'a> {
     pub value: &'a str,
     pub cursor: usize,
     pub show_cursor: bool,
+    pub variables: &'a Variables,
 }
 
 impl QueryParams {
@@ -23,6 +27,7 @@ impl QueryParams {
             value,
             cursor,
             show_cursor,
+            variables,
         } = props;
         let title = if active {
             "● Query Params"
@@ -34,7 +39,11 @@ impl QueryParams {
         } else {
             Style::default()
         };
-        let content = if value.is_empty() { "key: val" } else { value };
+        let content = if value.is_empty() {
+            Text::from("key: val")
+        } else {
+            highlight_placeholders(value, variables)
+        };
 
         frame.render_widget(
             Paragraph::new(content)
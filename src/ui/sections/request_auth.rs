@@ -0,0 +1,111 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{
+    types::{
+        auth::{AUTH_KEYS, mask_for_display},
+        environment::Variables,
+    },
+    ui::sections::variables::highlight_placeholders,
+};
+
+pub struct RequestAuth;
+
+pub struct RequestAuthProps<'a> {
+    pub area: Rect,
+    pub active: bool,
+    pub value: &'a str,
+    pub cursor: usize,
+    pub show_cursor: bool,
+    pub variables: &'a Variables,
+    pub error: Option<String>,
+    /// OAuth 2.0 token state, shown when there is no error.
+    pub status: Option<String>,
+}
+
+impl RequestAuth {
+    pub fn render(&self, frame: &mut Frame, props: RequestAuthProps<'_>) {
+        let RequestAuthProps {
+            area,
+            active,
+            value,
+            cursor,
+            show_cursor,
+            variables,
+            error,
+            status,
+        } = props;
+        let title = if active { "● Auth" } else { "○ Auth" };
+        let border_style = if active {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let content = if value.is_empty() {
+            Text::styled(AUTH_KEYS.join("\n"), Style::default().fg(Color::DarkGray))
+        } else {
+            // Secrets are masked character for character, so the cursor
+            // still lines up with the text being edited.
+            highlight_placeholders(&mask_for_display(value), variables)
+        };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+        if let Some(error) = error {
+            block = block.title_bottom(Line::styled(
+                format!("✗ {error}"),
+                Style::default().fg(Color::Red),
+            ));
+        } else if let Some(status) = status {
+            block = block.title_bottom(Line::styled(status, Style::default().fg(Color::DarkGray)));
+        }
+
+        frame.render_widget(
+            Paragraph::new(content)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+
+        if show_cursor {
+            let (line, col) = cursor_position(value, cursor, area.width.saturating_sub(2));
+            if area.height > 2 {
+                let line = line.min(area.height.saturating_sub(2) as usize);
+                let col = col.min(area.width.saturating_sub(2) as usize);
+                frame.set_cursor_position((area.x + col as u16 + 1, area.y + line as u16 + 1));
+            }
+        }
+    }
+}
+
+fn cursor_position(value: &str, cursor: usize, width: u16) -> (usize, usize) {
+    if width == 0 {
+        return (0, 0);
+    }
+    let width = width as usize;
+    let mut line = 0usize;
+    let mut col = 0usize;
+    for (idx, ch) in value.chars().enumerate() {
+        if idx >= cursor {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            col = 0;
+            continue;
+        }
+        col += 1;
+        if col >= width {
+            line += 1;
+            col = 0;
+        }
+    }
+    (line, col)
+}
@@ -2,9 +2,15 @@ use ratatui::{
     Frame,
     layout::Rect,
     style::{Color, Style},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
+use crate::{
+    types::environment::Variables,
+    ui::sections::{history::format_size, variables::highlight_placeholders},
+};
+
 pub struct RequestBody;
 
 pub struct RequestBodyProps<'a> {
@@ -13,7 +19,11 @@ pub struct RequestBodyProps<'a> {
     pub value: &'a str,
     pub cursor: usize,
     pub show_cursor: bool,
+    pub variables: &'a Variables,
     pub content_type: &'a str,
+    pub raw: bool,
+    pub validation: Option<Result<(), String>>,
+    pub file_notes: Vec<Option<Result<u64, String>>>,
 }
 
 impl RequestBody {
@@ -24,7 +34,11 @@ impl RequestBody {
             value,
             cursor,
             show_cursor,
+            variables,
             content_type,
+            raw,
+            validation,
+            file_notes,
         } = props;
         let title = if active {
             format!("● Request Body ({})", content_type)
@@ -36,16 +50,53 @@ impl RequestBody {
         } else {
             Style::default()
         };
-        let content = if value.is_empty() { "key: val" } else { value };
+        let content = if value.is_empty() {
+            Text::from(if raw {
+                "{\"key\": \"val\"}"
+            } else {
+                "key: val"
+            })
+        } else {
+            let mut text = highlight_placeholders(value, variables);
+            for (line, note) in text.lines.iter_mut().zip(file_notes) {
+                match note {
+                    Some(Ok(size)) => line.push_span(Span::styled(
+                        format!("  ({})", format_size(size as usize)),
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                    Some(Err(message)) => line.push_span(Span::styled(
+                        format!("  ✗ {message}"),
+                        Style::default().fg(Color::Red),
+                    )),
+                    None => {}
+                }
+            }
+            text
+        };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+        match validation {
+            Some(Ok(())) if !value.trim().is_empty() => {
+                block = block.title_bottom(Line::styled(
+                    "✓ valid JSON • p: Format",
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            Some(Err(message)) => {
+                block = block.title_bottom(Line::styled(
+                    format!("✗ {message}"),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            _ => {}
+        }
 
         frame.render_widget(
             Paragraph::new(content)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_style(border_style)
-                        .title(title),
-                )
+                .block(block)
                 .wrap(Wrap { trim: false }),
             area,
         );
@@ -0,0 +1,101 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{
+    types::{environment::Variables, send_options::OPTION_KEYS},
+    ui::sections::variables::highlight_placeholders,
+};
+
+pub struct RequestOptions;
+
+pub struct RequestOptionsProps<'a> {
+    pub area: Rect,
+    pub active: bool,
+    pub value: &'a str,
+    pub cursor: usize,
+    pub show_cursor: bool,
+    pub variables: &'a Variables,
+    pub error: Option<String>,
+}
+
+impl RequestOptions {
+    pub fn render(&self, frame: &mut Frame, props: RequestOptionsProps<'_>) {
+        let RequestOptionsProps {
+            area,
+            active,
+            value,
+            cursor,
+            show_cursor,
+            variables,
+            error,
+        } = props;
+        let title = if active { "● Options" } else { "○ Options" };
+        let border_style = if active {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let content = if value.is_empty() {
+            Text::styled(OPTION_KEYS.join("\n"), Style::default().fg(Color::DarkGray))
+        } else {
+            highlight_placeholders(value, variables)
+        };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+        if let Some(error) = error {
+            block = block.title_bottom(Line::styled(
+                format!("✗ {error}"),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        frame.render_widget(
+            Paragraph::new(content)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+
+        if show_cursor {
+            let (line, col) = cursor_position(value, cursor, area.width.saturating_sub(2));
+            if area.height > 2 {
+                let line = line.min(area.height.saturating_sub(2) as usize);
+                let col = col.min(area.width.saturating_sub(2) as usize);
+                frame.set_cursor_position((area.x + col as u16 + 1, area.y + line as u16 + 1));
+            }
+        }
+    }
+}
+
+fn cursor_position(value: &str, cursor: usize, width: u16) -> (usize, usize) {
+    if width == 0 {
+        return (0, 0);
+    }
+    let width = width as usize;
+    let mut line = 0usize;
+    let mut col = 0usize;
+    for (idx, ch) in value.chars().enumerate() {
+        if idx >= cursor {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            col = 0;
+            continue;
+        }
+        col += 1;
+        if col >= width {
+            line += 1;
+            col = 0;
+        }
+    }
+    (line, col)
+}
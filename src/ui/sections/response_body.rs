@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use once_cell::sync::Lazy;
 use ratatui::{
     Frame,
@@ -8,14 +10,30 @@ use ratatui::{
 };
 use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent, Highlighter};
 
+use crate::types::{
+    json_filter::{FilterSyntax, ResponseFilter},
+    json_tree::{JsonTree, NodeValue, TreeRow},
+    search::ResponseSearch,
+};
+
 pub struct ResponseBody;
 
 pub struct ResponseBodyProps<'a> {
     pub area: Rect,
     pub active: bool,
     pub body: Option<&'a str>,
+    pub formatted_body: Option<&'a str>,
     pub content_type: Option<&'a str>,
     pub scroll: u16,
+    pub scroll_x: u16,
+    pub search: &'a ResponseSearch,
+    pub search_query: &'a str,
+    pub search_cursor: usize,
+    pub filter: &'a ResponseFilter,
+    pub filter_query: &'a str,
+    pub filter_cursor: usize,
+    /// Shown instead of the text when the tree view is on.
+    pub tree: Option<&'a JsonTree>,
 }
 
 static HIGHLIGHT_NAMES: &[&str] = &[
@@ -46,50 +64,193 @@ static HIGHLIGHT_NAMES: &[&str] = &[
     "variable.builtin",
     "boolean",
     "null",
+    "label",
+    "text.title",
+    "text.literal",
+    "text.emphasis",
+    "text.strong",
+    "text.uri",
+    "text.reference",
+    "markup.heading",
+    "markup.raw",
 ];
 
 static JSON_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
-    let mut config = HighlightConfiguration::new(
+    highlight_config(
         tree_sitter_json::LANGUAGE.into(),
         "json",
         tree_sitter_json::HIGHLIGHTS_QUERY,
         "",
-        "",
     )
-    .expect("tree-sitter json highlight config");
-    config.configure(HIGHLIGHT_NAMES);
-    config
 });
 
 static HTML_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
-    let mut config = HighlightConfiguration::new(
+    highlight_config(
         tree_sitter_html::LANGUAGE.into(),
         "html",
         tree_sitter_html::HIGHLIGHTS_QUERY,
         tree_sitter_html::INJECTIONS_QUERY,
+    )
+});
+
+static XML_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
+    highlight_config(
+        tree_sitter_xml::LANGUAGE_XML.into(),
+        "xml",
+        tree_sitter_xml::XML_HIGHLIGHT_QUERY,
         "",
     )
-    .expect("tree-sitter html highlight config");
+});
+
+static YAML_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
+    highlight_config(
+        tree_sitter_yaml::LANGUAGE.into(),
+        "yaml",
+        tree_sitter_yaml::HIGHLIGHTS_QUERY,
+        "",
+    )
+});
+
+static JAVASCRIPT_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
+    highlight_config(
+        tree_sitter_javascript::LANGUAGE.into(),
+        "javascript",
+        tree_sitter_javascript::HIGHLIGHT_QUERY,
+        tree_sitter_javascript::INJECTIONS_QUERY,
+    )
+});
+
+static CSS_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
+    highlight_config(
+        tree_sitter_css::LANGUAGE.into(),
+        "css",
+        tree_sitter_css::HIGHLIGHTS_QUERY,
+        "",
+    )
+});
+
+static MARKDOWN_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
+    highlight_config(
+        tree_sitter_md::LANGUAGE.into(),
+        "markdown",
+        tree_sitter_md::HIGHLIGHT_QUERY_BLOCK,
+        tree_sitter_md::INJECTION_QUERY_BLOCK,
+    )
+});
+
+static MARKDOWN_INLINE_HIGHLIGHT: Lazy<HighlightConfiguration> = Lazy::new(|| {
+    highlight_config(
+        tree_sitter_md::INLINE_LANGUAGE.into(),
+        "markdown_inline",
+        tree_sitter_md::HIGHLIGHT_QUERY_INLINE,
+        tree_sitter_md::INJECTION_QUERY_INLINE,
+    )
+});
+
+fn highlight_config(
+    language: tree_sitter::Language,
+    name: &str,
+    highlights: &str,
+    injections: &str,
+) -> HighlightConfiguration {
+    let mut config = HighlightConfiguration::new(language, name, highlights, injections, "")
+        .unwrap_or_else(|e| panic!("tree-sitter {name} highlight config: {e}"));
     config.configure(HIGHLIGHT_NAMES);
     config
-});
+}
+
+/// Response formats with their own formatting or highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+    Json,
+    Html,
+    Xml,
+    Yaml,
+    JavaScript,
+    Css,
+    Markdown,
+}
+
+impl BodyKind {
+    /// Classifies a Content-Type, including structured syntax suffixes such
+    /// as `application/problem+json` or `application/soap+xml`.
+    fn of(content_type: Option<&str>) -> Option<Self> {
+        let essence = normalize_content_type(content_type);
+        let kind = match essence.as_str() {
+            "application/json" | "text/json" => Self::Json,
+            "text/html" => Self::Html,
+            "application/xml" | "text/xml" => Self::Xml,
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => Self::Yaml,
+            "application/javascript"
+            | "application/x-javascript"
+            | "application/ecmascript"
+            | "text/javascript"
+            | "text/ecmascript" => Self::JavaScript,
+            "text/css" => Self::Css,
+            "text/markdown" | "text/x-markdown" => Self::Markdown,
+            _ => match essence.rsplit_once('+')?.1 {
+                "json" => Self::Json,
+                "xml" => Self::Xml,
+                "yaml" => Self::Yaml,
+                _ => return None,
+            },
+        };
+        Some(kind)
+    }
+
+    fn highlight_config(self) -> &'static HighlightConfiguration {
+        match self {
+            Self::Json => &JSON_HIGHLIGHT,
+            Self::Html => &HTML_HIGHLIGHT,
+            Self::Xml => &XML_HIGHLIGHT,
+            Self::Yaml => &YAML_HIGHLIGHT,
+            Self::JavaScript => &JAVASCRIPT_HIGHLIGHT,
+            Self::Css => &CSS_HIGHLIGHT,
+            Self::Markdown => &MARKDOWN_HIGHLIGHT,
+        }
+    }
+}
+
+/// Grammar for an embedded language: `<script>` and `<style>` in HTML,
+/// fenced code blocks and inline spans in Markdown.
+fn injected_highlight_config(language: &str) -> Option<&'static HighlightConfiguration> {
+    match language.to_ascii_lowercase().as_str() {
+        "json" => Some(&JSON_HIGHLIGHT),
+        "html" => Some(&HTML_HIGHLIGHT),
+        "xml" => Some(&XML_HIGHLIGHT),
+        "yaml" | "yml" => Some(&YAML_HIGHLIGHT),
+        "javascript" | "js" => Some(&JAVASCRIPT_HIGHLIGHT),
+        "css" => Some(&CSS_HIGHLIGHT),
+        "markdown_inline" => Some(&MARKDOWN_INLINE_HIGHLIGHT),
+        _ => None,
+    }
+}
 
 impl ResponseBody {
-    pub fn render(
-        &self,
-        frame: &mut Frame,
-        area: Rect,
-        active: bool,
-        body: Option<&str>,
-        formatted_body: Option<&str>,
-        content_type: Option<&str>,
-        scroll: u16,
-        scroll_x: u16,
-    ) {
-        let title = if active {
-            "● Response"
+    pub fn render(&self, frame: &mut Frame, props: ResponseBodyProps<'_>) {
+        let ResponseBodyProps {
+            area,
+            active,
+            body,
+            formatted_body,
+            content_type,
+            scroll,
+            scroll_x,
+            search,
+            search_query,
+            search_cursor,
+            filter,
+            filter_query,
+            filter_cursor,
+            tree,
+        } = props;
+        let marker = if active { "●" } else { "○" };
+        let title = if tree.is_some() {
+            format!("{marker} Response (tree)")
+        } else if filter.enabled && filter.output.is_some() {
+            format!("{marker} Response (filtered)")
         } else {
-            "○ Response"
+            format!("{marker} Response")
         };
         let border_style = if active {
             Style::default().fg(Color::Cyan)
@@ -97,21 +258,60 @@ impl ResponseBody {
             Style::default()
         };
 
-        let content = display_content(body, formatted_body, content_type);
-        let text = if body.is_some() {
-            highlight_body(&content, content_type).unwrap_or_else(|| Text::from(content))
+        let text = if let Some(tree) = tree {
+            tree_text(tree, active)
         } else {
-            Text::from(content)
+            let content = display_content(body, formatted_body, content_type);
+            let mut text = if body.is_some() {
+                highlight_body(&content, content_type).unwrap_or_else(|| Text::from(content))
+            } else {
+                Text::from(content)
+            };
+            if body.is_some() {
+                highlight_matches(&mut text, search);
+            }
+            text
         };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+        // Bottom titles sit one column in from the corner, a space apart.
+        let mut cursor_x = None;
+        let mut offset = 1;
+        if filter.editing || !filter_query.is_empty() {
+            let (line, prefix) = filter_line(filter, filter_query);
+            if filter.editing {
+                cursor_x = Some(offset + prefix + filter_cursor);
+            }
+            offset += line.width() + 1;
+            block = block.title_bottom(line);
+        }
+        if tree.is_some() && active && offset == 1 {
+            block = block.title_bottom(Line::styled(
+                "Space: Fold • h/l: Collapse/Expand • *: Expand all • 0-9: Depth • y: Copy path • v: Text",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if search.editing || !search_query.is_empty() {
+            if search.editing {
+                cursor_x = Some(offset + 1 + search_cursor);
+            }
+            block = block.title_bottom(search_line(search, search_query));
+        }
         frame.render_widget(
-            Paragraph::new(text).scroll((scroll, scroll_x)).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(border_style)
-                    .title(title),
-            ),
+            Paragraph::new(text).scroll((scroll, scroll_x)).block(block),
             area,
         );
+
+        if let Some(x) = cursor_x
+            && area.height > 1
+        {
+            let max_x = area.width.saturating_sub(2) as usize;
+            let x = x.min(max_x) as u16;
+            frame.set_cursor_position((area.x + x, area.y + area.height - 1));
+        }
     }
 
     pub fn line_count(
@@ -168,10 +368,12 @@ fn display_content<'a>(
 }
 
 fn highlight_body(body: &str, content_type: Option<&str>) -> Option<Text<'static>> {
-    let config = content_type.and_then(map_content_type_to_highlight_config)?;
+    let config = BodyKind::of(content_type)?.highlight_config();
     let mut highlighter = Highlighter::new();
     let events = highlighter
-        .highlight(config, body.as_bytes(), None, |_| None)
+        .highlight(config, body.as_bytes(), None, |language| {
+            injected_highlight_config(language)
+        })
         .ok()?;
     let mut lines: Vec<Line> = Vec::new();
     let mut current: Vec<Span> = Vec::new();
@@ -206,15 +408,226 @@ fn highlight_body(body: &str, content_type: Option<&str>) -> Option<Text<'static
     Some(Text::from(lines))
 }
 
+/// One line per visible tree row, with the cursor row highlighted.
+fn tree_text(tree: &JsonTree, active: bool) -> Text<'static> {
+    let punctuation = style_for_highlight_name("punctuation");
+    let dim = Style::default().fg(Color::DarkGray);
+    let lines = tree.rows().iter().enumerate().map(|(row_index, row)| {
+        let (TreeRow::Open(index) | TreeRow::Close(index)) = *row;
+        let node = tree.node(index);
+        let mut spans = vec![Span::raw("  ".repeat(node.depth))];
+        match *row {
+            TreeRow::Close(_) => {
+                let bracket = if matches!(node.value, NodeValue::Object(_)) {
+                    "}"
+                } else {
+                    "]"
+                };
+                spans.push(Span::styled(format!("  {bracket}"), punctuation));
+            }
+            TreeRow::Open(_) => {
+                let collapsed = tree.is_collapsed(index);
+                let marker = match node.value {
+                    NodeValue::Leaf(_) => "  ",
+                    _ if collapsed => "▸ ",
+                    _ => "▾ ",
+                };
+                spans.push(Span::styled(marker, dim));
+                if let Some(key) = &node.key {
+                    let style = if key.starts_with('"') {
+                        style_for_highlight_name("property")
+                    } else {
+                        dim
+                    };
+                    spans.push(Span::styled(key.clone(), style));
+                    spans.push(Span::styled(": ", punctuation));
+                }
+                match &node.value {
+                    NodeValue::Leaf(value) => {
+                        spans.push(Span::styled(value.clone(), leaf_style(value)));
+                    }
+                    NodeValue::Object(count) if collapsed => {
+                        spans.push(Span::styled("{…}", punctuation));
+                        spans.push(Span::styled(format!(" {}", plural(*count, "key")), dim));
+                    }
+                    NodeValue::Array(count) if collapsed => {
+                        spans.push(Span::styled("[…]", punctuation));
+                        spans.push(Span::styled(format!(" {}", plural(*count, "item")), dim));
+                    }
+                    NodeValue::Object(_) => spans.push(Span::styled("{", punctuation)),
+                    NodeValue::Array(_) => spans.push(Span::styled("[", punctuation)),
+                }
+            }
+        }
+        let line = Line::from(spans);
+        if active && row_index == tree.cursor {
+            line.style(Style::default().bg(Color::DarkGray))
+        } else {
+            line
+        }
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+fn leaf_style(value: &str) -> Style {
+    match value.chars().next() {
+        Some('"') => style_for_highlight_name("string"),
+        Some('-' | '0'..='9') => style_for_highlight_name("number"),
+        Some('{' | '[') => style_for_highlight_name("punctuation"),
+        _ => style_for_highlight_name("constant.builtin"),
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// The filter expression in the bottom border, and the width of the label
+/// before it.
+fn filter_line<'a>(filter: &ResponseFilter, query: &'a str) -> (Line<'a>, usize) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let label = format!("{}: ", FilterSyntax::of(query).label());
+    let prefix = label.chars().count();
+    let mut spans = vec![
+        Span::styled(label, Style::default().fg(Color::Cyan)),
+        Span::raw(query),
+    ];
+    if let Some(error) = &filter.error {
+        spans.push(Span::styled(
+            format!(" ✗ {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    } else if filter.is_running() {
+        spans.push(Span::styled(" running…", dim));
+    } else if filter.editing {
+        spans.push(Span::styled(" Enter: Done • Esc: Clear", dim));
+    } else if filter.enabled {
+        spans.push(Span::styled(" F: Full body", dim));
+    } else {
+        spans.push(Span::styled(" F: Filtered", dim));
+    }
+    (Line::from(spans), prefix)
+}
+
+/// The `/query` line in the bottom border, with the active toggles.
+fn search_line<'a>(search: &ResponseSearch, query: &'a str) -> Line<'a> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let toggle = |label: &'static str, on: bool| {
+        Span::styled(
+            label,
+            if on {
+                Style::default().fg(Color::Yellow)
+            } else {
+                dim
+            },
+        )
+    };
+    let mut spans = vec![
+        Span::raw("/"),
+        Span::raw(query),
+        Span::raw(" "),
+        toggle("[.*]", search.regex),
+        toggle("[Aa]", search.case_sensitive),
+    ];
+    if let Some(error) = &search.error {
+        spans.push(Span::styled(
+            format!(" ✗ {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    } else if search.editing {
+        spans.push(Span::styled(
+            " Enter: Done • Ctrl+R: Regex • Ctrl+T: Case • Esc: Clear",
+            dim,
+        ));
+    } else {
+        spans.push(Span::styled(" n/N: Next/Prev • Esc: Clear", dim));
+    }
+    Line::from(spans)
+}
+
+/// Paints search matches over the existing (syntax-highlighted) spans.
+fn highlight_matches(text: &mut Text<'_>, search: &ResponseSearch) {
+    let match_style = Style::default().bg(Color::Yellow).fg(Color::Black);
+    let current_style = match_style.bg(Color::LightRed).add_modifier(Modifier::BOLD);
+    let mut idx = 0;
+    while idx < search.matches.len() {
+        let line_idx = search.matches[idx].line;
+        let mut ranges = Vec::new();
+        while let Some(found) = search.matches.get(idx).filter(|m| m.line == line_idx) {
+            let style = if idx == search.current {
+                current_style
+            } else {
+                match_style
+            };
+            ranges.push((found.range.clone(), style));
+            idx += 1;
+        }
+        if let Some(line) = text.lines.get_mut(line_idx) {
+            restyle_ranges(line, &ranges);
+        }
+    }
+}
+
+/// Splits the spans of `line` at the byte ranges and patches their style.
+fn restyle_ranges(line: &mut Line<'_>, ranges: &[(Range<usize>, Style)]) {
+    let mut spans = Vec::with_capacity(line.spans.len() + ranges.len() * 2);
+    let mut offset = 0;
+    for span in line.spans.drain(..) {
+        let content = span.content.as_ref();
+        let len = content.len();
+        let mut cursor = 0;
+        for (range, style) in ranges {
+            if range.end <= offset || range.start >= offset + len {
+                continue;
+            }
+            let start = range.start.max(offset) - offset;
+            let end = range.end.min(offset + len) - offset;
+            if !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+                continue;
+            }
+            if start > cursor {
+                spans.push(Span::styled(content[cursor..start].to_string(), span.style));
+            }
+            spans.push(Span::styled(
+                content[start..end].to_string(),
+                span.style.patch(*style),
+            ));
+            cursor = end;
+        }
+        if cursor < len {
+            spans.push(Span::styled(content[cursor..].to_string(), span.style));
+        }
+        offset += len;
+    }
+    line.spans = spans;
+}
+
 fn format_body(body: &str, content_type: Option<&str>) -> Option<String> {
-    let normalized = content_type
+    match BodyKind::of(content_type)? {
+        BodyKind::Json => format_json(body),
+        BodyKind::Html => format_html(body),
+        BodyKind::Xml => format_xml(body),
+        BodyKind::Css => format_css(body),
+        BodyKind::Yaml | BodyKind::JavaScript | BodyKind::Markdown => None,
+    }
+}
+
+fn normalize_content_type(content_type: Option<&str>) -> String {
+    content_type
         .and_then(|ct| ct.split(';').next())
         .unwrap_or_default()
         .trim()
-        .to_ascii_lowercase();
-    match normalized.as_str() {
-        "application/json" | "text/json" | "application/ld+json" => format_json(body),
-        "text/html" => format_html(body),
+        .to_ascii_lowercase()
+}
+
+/// The parsed body of a JSON response, as `format_json` sees it.
+pub fn parse_json_body(body: &str, content_type: Option<&str>) -> Option<serde_json::Value> {
+    match BodyKind::of(content_type)? {
+        BodyKind::Json => serde_json::from_str(body).ok(),
         _ => None,
     }
 }
@@ -225,12 +638,193 @@ fn format_json(body: &str) -> Option<String> {
 }
 
 fn format_html(body: &str) -> Option<String> {
+    let tokens = markup_tokens(body);
+    let mut lines = Vec::new();
+    let mut indent = 0usize;
+    for token in tokens {
+        if token.starts_with('<') && token.ends_with('>') {
+            let tag = token.trim();
+            let is_comment = tag.starts_with("<!--");
+            let is_doctype = tag.starts_with("<!");
+            let is_pi = tag.starts_with("<?");
+            let is_closing = tag.starts_with("</");
+            let is_self_closing =
+                tag.ends_with("/>") || is_comment || is_doctype || is_pi || is_void_element(tag);
+
+            if is_closing {
+                indent = indent.saturating_sub(1);
+            }
+
+            lines.push(format!("{}{}", "  ".repeat(indent), tag));
+
+            if !is_closing && !is_self_closing {
+                indent += 1;
+            }
+        } else {
+            lines.push(format!("{}{}", "  ".repeat(indent), token));
+        }
+    }
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Like `format_html`, but without void elements, and an element holding
+/// only text stays on one line: `<id>42</id>`.
+fn format_xml(body: &str) -> Option<String> {
+    let tokens = markup_tokens(body);
+    let is_tag = |token: &str| token.starts_with('<') && token.ends_with('>');
+    let mut lines = Vec::new();
+    let mut indent = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+        let pad = "  ".repeat(indent);
+        if !is_tag(token) {
+            lines.push(format!("{pad}{token}"));
+            i += 1;
+            continue;
+        }
+        if token.starts_with("</") {
+            indent = indent.saturating_sub(1);
+            lines.push(format!("{}{token}", "  ".repeat(indent)));
+            i += 1;
+            continue;
+        }
+        if token.ends_with("/>") || token.starts_with("<!") || token.starts_with("<?") {
+            lines.push(format!("{pad}{token}"));
+            i += 1;
+            continue;
+        }
+        let closes = |next: Option<&String>| next.is_some_and(|next| next.starts_with("</"));
+        match tokens.get(i + 1) {
+            Some(text) if !is_tag(text) && closes(tokens.get(i + 2)) => {
+                lines.push(format!("{pad}{token}{text}{}", tokens[i + 2]));
+                i += 3;
+            }
+            next if closes(next) => {
+                lines.push(format!("{pad}{token}{}", tokens[i + 1]));
+                i += 2;
+            }
+            _ => {
+                lines.push(format!("{pad}{token}"));
+                indent += 1;
+                i += 1;
+            }
+        }
+    }
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Puts each declaration on its own line and indents nested blocks.
+/// Strings, comments and parentheses (`url(data:…;base64,…)`) are kept
+/// intact.
+fn format_css(body: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut depth = 0usize;
+    let mut parens = 0usize;
+    let mut quote = None;
+    let mut chars = body.chars().peekable();
+    let flush = |line: &mut String, lines: &mut Vec<String>, depth: usize| {
+        let text = line.trim();
+        if !text.is_empty() {
+            lines.push(format!("{}{text}", "  ".repeat(depth)));
+        }
+        line.clear();
+    };
+    while let Some(ch) = chars.next() {
+        if let Some(open) = quote {
+            line.push(ch);
+            if ch == '\\' {
+                line.extend(chars.next());
+            } else if ch == open {
+                quote = None;
+            }
+            continue;
+        }
+        match ch {
+            '"' | '\'' => {
+                quote = Some(ch);
+                line.push(ch);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                line.push_str("/*");
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    line.push(c);
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '(' => {
+                parens += 1;
+                line.push(ch);
+            }
+            ')' => {
+                parens = parens.saturating_sub(1);
+                line.push(ch);
+            }
+            '{' if parens == 0 => {
+                line.truncate(line.trim_end().len());
+                line.push_str(" {");
+                flush(&mut line, &mut lines, depth);
+                depth += 1;
+            }
+            '}' if parens == 0 => {
+                flush(&mut line, &mut lines, depth);
+                depth = depth.saturating_sub(1);
+                lines.push(format!("{}}}", "  ".repeat(depth)));
+            }
+            ';' if parens == 0 => {
+                line.push(ch);
+                flush(&mut line, &mut lines, depth);
+            }
+            c if c.is_whitespace() => {
+                if !line.is_empty() && !line.ends_with(' ') {
+                    line.push(' ');
+                }
+            }
+            _ => line.push(ch),
+        }
+    }
+    flush(&mut line, &mut lines, depth);
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Splits markup into tags and trimmed text. Quoted attribute values,
+/// comments and CDATA sections may contain `>`.
+fn markup_tokens(body: &str) -> Vec<String> {
     let mut tokens = Vec::new();
     let mut buf = String::new();
     let mut in_tag = false;
     let mut in_single_quote = false;
     let mut in_double_quote = false;
     for ch in body.chars() {
+        if in_tag && (buf.starts_with("<!--") || buf.starts_with("<![CDATA[")) {
+            buf.push(ch);
+            if ch == '>' && (buf.ends_with("-->") || buf.ends_with("]]>")) {
+                tokens.push(std::mem::take(&mut buf));
+                in_tag = false;
+            }
+            continue;
+        }
         if ch == '<' {
             if !buf.is_empty() {
                 let text = buf.trim();
@@ -267,38 +861,7 @@ fn format_html(body: &str) -> Option<String> {
             tokens.push(text.to_string());
         }
     }
-
-    let mut lines = Vec::new();
-    let mut indent = 0usize;
-    for token in tokens {
-        if token.starts_with('<') && token.ends_with('>') {
-            let tag = token.trim();
-            let is_comment = tag.starts_with("<!--");
-            let is_doctype = tag.starts_with("<!");
-            let is_pi = tag.starts_with("<?");
-            let is_closing = tag.starts_with("</");
-            let is_self_closing =
-                tag.ends_with("/>") || is_comment || is_doctype || is_pi || is_void_element(tag);
-
-            if is_closing {
-                indent = indent.saturating_sub(1);
-            }
-
-            lines.push(format!("{}{}", "  ".repeat(indent), tag));
-
-            if !is_closing && !is_self_closing {
-                indent += 1;
-            }
-        } else {
-            lines.push(format!("{}{}", "  ".repeat(indent), token));
-        }
-    }
-
-    if lines.is_empty() {
-        None
-    } else {
-        Some(lines.join("\n"))
-    }
+    tokens
 }
 
 fn is_void_element(tag: &str) -> bool {
@@ -329,22 +892,6 @@ fn is_void_element(tag: &str) -> bool {
     )
 }
 
-fn map_content_type_to_highlight_config(
-    content_type: &str,
-) -> Option<&'static HighlightConfiguration> {
-    let normalized = content_type
-        .split(';')
-        .next()
-        .unwrap_or(content_type)
-        .trim()
-        .to_ascii_lowercase();
-    match normalized.as_str() {
-        "application/json" | "text/json" | "application/ld+json" => Some(&JSON_HIGHLIGHT),
-        "text/html" => Some(&HTML_HIGHLIGHT),
-        _ => None,
-    }
-}
-
 fn style_for_highlight_name(name: &str) -> Style {
     match name {
         "string" | "string.special" => Style::default().fg(Color::Green),
@@ -355,17 +902,52 @@ fn style_for_highlight_name(name: &str) -> Style {
         "comment" => Style::default()
             .fg(Color::DarkGray)
             .add_modifier(Modifier::ITALIC),
-        "punctuation" | "punctuation.bracket" | "punctuation.delimiter" | "punctuation.special" => {
-            Style::default().fg(Color::DarkGray)
-        }
+        "punctuation"
+        | "punctuation.bracket"
+        | "punctuation.delimiter"
+        | "punctuation.special"
+        | "markup.heading" => Style::default().fg(Color::DarkGray),
         "type" | "type.builtin" | "constructor" => Style::default().fg(Color::LightBlue),
+        "label" => Style::default().fg(Color::Magenta),
+        "text.title" => Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+        "text.literal" | "markup.raw" => Style::default().fg(Color::Green),
+        "text.emphasis" => Style::default().add_modifier(Modifier::ITALIC),
+        "text.strong" => Style::default().add_modifier(Modifier::BOLD),
+        "text.uri" => Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::UNDERLINED),
+        "text.reference" => Style::default().fg(Color::LightBlue),
         _ => Style::default(),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::format_html;
+    use ratatui::{
+        style::{Color, Style},
+        text::{Line, Span},
+    };
+
+    use super::{BodyKind, format_css, format_html, format_xml, highlight_body, restyle_ranges};
+
+    #[test]
+    fn restyle_ranges_splits_spans_and_keeps_their_style() {
+        let key = Style::default().fg(Color::Blue);
+        let mark = Style::default().bg(Color::Yellow);
+        let mut line = Line::from(vec![Span::styled("\"name\"", key), Span::raw(": 1")]);
+        restyle_ranges(&mut line, &[(3..7, mark)]);
+        assert_eq!(
+            line.spans,
+            vec![
+                Span::styled("\"na", key),
+                Span::styled("me\"", key.patch(mark)),
+                Span::styled(":", mark),
+                Span::raw(" 1"),
+            ]
+        );
+    }
 
     #[test]
     fn format_html_ignores_gt_inside_double_quotes() {
@@ -389,4 +971,85 @@ mod tests {
 
         assert_eq!(format_html(input).as_deref(), Some(expected.as_str()));
     }
+
+    #[test]
+    fn body_kind_matches_structured_suffixes() {
+        let kind = |content_type| BodyKind::of(Some(content_type));
+        assert_eq!(kind("application/problem+json"), Some(BodyKind::Json));
+        assert_eq!(
+            kind("application/vnd.api+json; charset=utf-8"),
+            Some(BodyKind::Json)
+        );
+        assert_eq!(kind("application/soap+xml"), Some(BodyKind::Xml));
+        assert_eq!(kind("Text/XML"), Some(BodyKind::Xml));
+        assert_eq!(kind("application/x-yaml"), Some(BodyKind::Yaml));
+        assert_eq!(kind("text/javascript"), Some(BodyKind::JavaScript));
+        assert_eq!(kind("text/markdown"), Some(BodyKind::Markdown));
+        assert_eq!(kind("application/octet-stream"), None);
+    }
+
+    #[test]
+    fn format_xml_keeps_text_elements_on_one_line() {
+        let input = r#"<?xml version="1.0"?><soap:Envelope><soap:Body><id>42</id><empty></empty><note><![CDATA[a > b]]></note><br/></soap:Body></soap:Envelope>"#;
+        let expected = [
+            r#"<?xml version="1.0"?>"#,
+            "<soap:Envelope>",
+            "  <soap:Body>",
+            "    <id>42</id>",
+            "    <empty></empty>",
+            "    <note>",
+            "      <![CDATA[a > b]]>",
+            "    </note>",
+            "    <br/>",
+            "  </soap:Body>",
+            "</soap:Envelope>",
+        ]
+        .join("\n");
+
+        assert_eq!(format_xml(input).as_deref(), Some(expected.as_str()));
+    }
+
+    #[test]
+    fn format_css_splits_declarations_and_nests_blocks() {
+        let input = "a{color:red;background:url(data:x;base64,AA)}@media (x){b{content:\"};{\"}}";
+        let expected = [
+            "a {",
+            "  color:red;",
+            "  background:url(data:x;base64,AA)",
+            "}",
+            "@media (x) {",
+            "  b {",
+            "    content:\"};{\"",
+            "  }",
+            "}",
+        ]
+        .join("\n");
+
+        assert_eq!(format_css(input).as_deref(), Some(expected.as_str()));
+    }
+
+    #[test]
+    fn every_body_kind_has_a_working_grammar() {
+        let samples = [
+            (
+                "application/xml",
+                "<?xml version=\"1.0\"?><ns:a b=\"1\"><![CDATA[x < y]]></ns:a>",
+            ),
+            ("application/yaml", "a: [1, true]"),
+            ("text/javascript", "const a = () => 1;"),
+            ("text/css", "a { color: red; }"),
+            (
+                "text/markdown",
+                "# Title\n\n*hi* `code`\n\n```json\n{}\n```",
+            ),
+            ("text/html", "<style>a{}</style><script>let a;</script>"),
+        ];
+        for (content_type, body) in samples {
+            let text = highlight_body(body, Some(content_type)).expect(content_type);
+            assert!(
+                text.lines.iter().any(|line| line.spans.len() > 1),
+                "{content_type}"
+            );
+        }
+    }
 }
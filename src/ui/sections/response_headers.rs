@@ -14,6 +14,8 @@ pub struct ResponseHeadersProps<'a> {
     pub status: Option<&'a str>,
     pub headers: Option<&'a str>,
     pub response_time: u128,
+    /// Encoding the body was decoded with, e.g. `windows-1252`.
+    pub charset: Option<String>,
 }
 
 impl ResponseHeaders {
@@ -24,6 +26,7 @@ impl ResponseHeaders {
             status,
             headers,
             response_time,
+            charset,
         } = props;
         let indicator = if active { "● " } else { "○ " };
         let mut title_spans = vec![Span::raw(indicator), Span::raw("Response headers")];
@@ -37,6 +40,11 @@ impl ResponseHeaders {
             ));
         }
 
+        if let Some(charset) = charset {
+            title_spans.push(Span::raw(" | "));
+            title_spans.push(Span::styled(charset, Style::default().fg(Color::DarkGray)));
+        }
+
         let border_style = if active {
             Style::default().fg(Color::Cyan)
         } else {
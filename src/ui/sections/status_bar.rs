@@ -14,6 +14,8 @@ pub struct StatusBarProps<'a> {
     pub active_panel: ActivePanel,
     pub is_loading: bool,
     pub error: Option<&'a str>,
+    pub message: Option<&'a str>,
+    pub search: Option<&'a str>,
 }
 
 impl StatusBar {
@@ -24,6 +26,8 @@ impl StatusBar {
             active_panel,
             is_loading,
             error,
+            message,
+            search,
         } = props;
         let mode_label = match mode {
             Mode::Normal => "NORMAL",
@@ -32,28 +36,36 @@ impl StatusBar {
         let focus_label = match active_panel {
             ActivePanel::Url => "URL",
             ActivePanel::ReqQuery => "Query",
+            ActivePanel::ReqOptions => "Options",
             ActivePanel::ReqHeaders => "ReqHeaders",
+            ActivePanel::ReqAuth => "Auth",
             ActivePanel::ReqBody => "ReqBody",
             ActivePanel::ResHeaders => "ResHeaders",
             ActivePanel::ResBody => "ResBody",
+            ActivePanel::Collections => "Collections",
         };
         let hint = match mode {
             Mode::Normal => {
-                "i: Edit • Enter: Send • b/B: Body • Tab/Shift+Tab: Focus • h/l: ResBody X-Scroll"
+                "i: Edit • Enter: Send • b/B: Body • H: History • K: Cookies • c/s: Collections/Save • /: Search • f: Filter • v: Tree • y: Yank • w/W: Save raw/formatted • Tab/Shift+Tab: Focus • h/l: ResBody X-Scroll"
             }
             Mode::Edit => "Esc: Normal • Enter: Send (URL) • Ctrl+Enter: Send",
         };
-        let status = if let Some(error) = error {
+        let mut status = if let Some(error) = error {
             format!("ERROR: {}", error)
         } else if is_loading {
             "Loading...".to_string()
+        } else if let Some(message) = message {
+            message.to_string()
         } else {
             "Ready".to_string()
         };
+        if let Some(search) = search {
+            status = format!("{search} • {status}");
+        }
 
         frame.render_widget(
             Paragraph::new(format!(
-                "{mode} • Focus: {focus} • {hint} • {status}",
+                "{mode} • Focus: {focus} • {status} • {hint}",
                 mode = mode_label,
                 focus = focus_label,
                 hint = hint,
@@ -4,21 +4,29 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::types::app::{ActivePanel, App, Mode};
+use crate::{
+    types::app::{ActivePanel, App, Mode},
+    ui::sections::variables::highlight_placeholders,
+};
 
 pub struct UrlBar<'a>(pub &'a App<'a>);
 
 impl<'a> UrlBar<'a> {
     pub fn render(&self, frame: &mut Frame, area: Rect) {
         let active = self.0.app_state.active_panel == ActivePanel::Url;
-        let title = if active { "● URL" } else { "○ URL" };
+        let indicator = if active { "● URL" } else { "○ URL" };
+        let title = match self.0.environments.active_name() {
+            Some(env) => format!("{indicator} [{env}]"),
+            None => indicator.to_string(),
+        };
         let border_style = if active {
             Style::default().fg(Color::Cyan)
         } else {
             Style::default()
         };
 
-        let widget = Paragraph::new(self.0.url_input.value())
+        let variables = self.0.environments.active_variables();
+        let widget = Paragraph::new(highlight_placeholders(self.0.url_input.value(), variables))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
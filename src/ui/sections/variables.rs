@@ -0,0 +1,37 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+use crate::types::environment::{Variables, find_placeholders};
+
+/// Renders `value` with `{{name}}` placeholders coloured by whether the active
+/// environment defines them.
+pub fn highlight_placeholders(value: &str, vars: &Variables) -> Text<'static> {
+    let lines = value
+        .split('\n')
+        .map(|line| {
+            let mut spans = Vec::new();
+            let mut last = 0;
+            for (range, name) in find_placeholders(line) {
+                if range.start > last {
+                    spans.push(Span::raw(line[last..range.start].to_string()));
+                }
+                let style = if vars.contains_key(name) {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default()
+                        .fg(Color::Red)
+                        .add_modifier(Modifier::UNDERLINED)
+                };
+                spans.push(Span::styled(line[range.clone()].to_string(), style));
+                last = range.end;
+            }
+            if last < line.len() {
+                spans.push(Span::raw(line[last..].to_string()));
+            }
+            Line::from(spans)
+        })
+        .collect::<Vec<_>>();
+    Text::from(lines)
+}